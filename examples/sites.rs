@@ -8,10 +8,21 @@ pub async fn main() -> Result<()> {
 
     let client = Client::from_env()?;
 
+    let mut req = GetSites::default();
+    if let Some(search) = std::env::args().nth(1) {
+        req = req.search(search);
+    }
+
     info!("fetching sites");
-    let sites = GetSites::default().send(&client).await?;
+    let mut pages = req.pages();
+    let mut count = 0;
 
     println!("Fetched sites:");
-    println!("{:#?}", &sites);
+    while let Some(site) = pages.next(&client).await? {
+        println!("{:#?}", &site);
+        count += 1;
+    }
+
+    println!("Fetched {count} sites");
     Ok(())
 }
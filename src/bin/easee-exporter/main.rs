@@ -0,0 +1,377 @@
+//! Serves charger and site metrics for Prometheus on `/metrics`.
+//!
+//! Chargers are polled in the background every `POLL_INTERVAL` seconds (60), and `/metrics`
+//! serves the result of the latest poll, so scraping often doesn't cost any api requests.
+//!
+//! Configured through the environment:
+//! * `EASEE_ACCESS_TOKEN`, `EASEE_REFRESH_TOKEN` - see `Client::from_env`
+//! * `LISTEN` (0.0.0.0:9757)
+//! * `POLL_INTERVAL` in seconds (60)
+
+mod metrics;
+
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use axum::{Router, extract::State, routing::get};
+use easeeapi::{
+    ChargerState, Client, DateTime, OptionalResult, Site,
+    requests::{GetChargerState, GetOngoingSession, GetSite, GetSites},
+};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use metrics::Metrics;
+
+#[derive(Default)]
+struct Exporter {
+    rendered: String,
+    scrapes: u64,
+    /// Failed requests per endpoint
+    api_errors: BTreeMap<&'static str, u64>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().init();
+
+    let var = |name, default: &str| std::env::var(name).unwrap_or_else(|_| default.into());
+    let listen = var("LISTEN", "0.0.0.0:9757");
+    let poll_interval = Duration::from_secs(
+        var("POLL_INTERVAL", "60")
+            .parse()
+            .context("POLL_INTERVAL")?,
+    );
+
+    let client = Client::from_env()?;
+    let exporter = Arc::new(RwLock::new(Exporter::default()));
+
+    tokio::spawn(poll(client, exporter.clone(), poll_interval));
+
+    let app = Router::new()
+        .route("/metrics", get(serve_metrics))
+        .with_state(exporter);
+
+    let listener = tokio::net::TcpListener::bind(&listen)
+        .await
+        .with_context(|| format!("binding {listen}"))?;
+    info!("serving metrics on http://{listen}/metrics");
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    Ok(())
+}
+
+async fn serve_metrics(State(exporter): State<Arc<RwLock<Exporter>>>) -> String {
+    exporter.read().await.rendered.clone()
+}
+
+async fn poll(client: Client, exporter: Arc<RwLock<Exporter>>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let started = Instant::now();
+        let mut errors = Vec::new();
+        let mut metrics = Metrics::default();
+
+        let success = match scrape(&client, &mut metrics, &mut errors).await {
+            Ok(()) => true,
+            Err(err) => {
+                warn!("scraping sites failed: {err:#}");
+                false
+            }
+        };
+
+        let mut exporter = exporter.write().await;
+        exporter.scrapes += 1;
+        for endpoint in errors {
+            *exporter.api_errors.entry(endpoint).or_default() += 1;
+        }
+
+        metrics.gauge(
+            "easee_scrape_success",
+            "Whether the last poll of all sites succeeded",
+            &[],
+            if success { 1.0 } else { 0.0 },
+        );
+        metrics.gauge(
+            "easee_scrape_duration_seconds",
+            "How long the last poll took",
+            &[],
+            started.elapsed().as_secs_f64(),
+        );
+        metrics.gauge(
+            "easee_scrape_timestamp_seconds",
+            "When the last poll finished",
+            &[],
+            DateTime::now_utc().0.unix_timestamp() as f64,
+        );
+        metrics.counter(
+            "easee_scrapes_total",
+            "Polls since the exporter started",
+            &[],
+            exporter.scrapes as f64,
+        );
+        for (endpoint, count) in &exporter.api_errors {
+            metrics.counter(
+                "easee_api_errors_total",
+                "Failed api requests",
+                &[("endpoint", endpoint)],
+                *count as f64,
+            );
+        }
+
+        exporter.rendered = metrics.render();
+    }
+}
+
+/// Fails only if the sites can't be listed, failing chargers are left out and counted
+async fn scrape(
+    client: &Client,
+    metrics: &mut Metrics,
+    errors: &mut Vec<&'static str>,
+) -> Result<()> {
+    let mut pages = GetSites::default().pages();
+
+    while let Some(site) = pages
+        .next(client)
+        .await
+        .inspect_err(|_| errors.push("sites"))?
+    {
+        let site = match GetSite(site.id).send(client).await {
+            Ok(site) => site,
+            Err(err) => {
+                warn!(site_id = %site.id, "fetching site failed: {err}");
+                errors.push("site");
+                continue;
+            }
+        };
+
+        scrape_site(client, &site, metrics, errors).await;
+    }
+
+    Ok(())
+}
+
+async fn scrape_site(
+    client: &Client,
+    site: &Site,
+    metrics: &mut Metrics,
+    errors: &mut Vec<&'static str>,
+) {
+    let site_id = site.id.to_string();
+    let site_labels = [
+        ("site_id", site_id.as_str()),
+        ("site_name", site.name.as_str()),
+    ];
+
+    metrics.gauge(
+        "easee_site_rated_current_amperes",
+        "Rated current of the site",
+        &site_labels,
+        site.rated_current,
+    );
+
+    for circuit in &site.circuits {
+        let circuit_labels = [
+            site_labels[0],
+            site_labels[1],
+            ("circuit", circuit.panel_name.as_str()),
+        ];
+
+        metrics.gauge(
+            "easee_circuit_rated_current_amperes",
+            "Rated current of the circuit",
+            &circuit_labels,
+            circuit.rated_current,
+        );
+
+        let mut circuit_reported = false;
+        for charger in &circuit.chargers {
+            let state = match GetChargerState::new(&charger.id).send(client).await {
+                Ok(state) => state,
+                Err(err) => {
+                    warn!(
+                        charger_id = charger.id,
+                        "fetching charger state failed: {err}"
+                    );
+                    errors.push("charger_state");
+                    continue;
+                }
+            };
+
+            let session = match GetOngoingSession::new(&charger.id)
+                .send(client)
+                .await
+                .optional()
+            {
+                Ok(session) => session,
+                Err(err) => {
+                    warn!(
+                        charger_id = charger.id,
+                        "fetching ongoing session failed: {err}"
+                    );
+                    errors.push("ongoing_session");
+                    None
+                }
+            };
+
+            let labels = [
+                circuit_labels[0],
+                circuit_labels[1],
+                circuit_labels[2],
+                ("charger_id", charger.id.as_str()),
+            ];
+            charger_metrics(metrics, &labels, &state, session.map(|s| s.kilo_watt_hours));
+
+            // Every charger reports the dynamic current of its circuit, one is enough
+            if !circuit_reported {
+                circuit_reported = true;
+
+                let phases = [
+                    ("1", state.dynamic_circuit_current_p1),
+                    ("2", state.dynamic_circuit_current_p2),
+                    ("3", state.dynamic_circuit_current_p3),
+                ];
+                for (phase, current) in phases {
+                    if let Some(current) = current {
+                        metrics.gauge(
+                            "easee_circuit_dynamic_current_amperes",
+                            "Dynamic current limit of the circuit per phase",
+                            &[
+                                circuit_labels[0],
+                                circuit_labels[1],
+                                circuit_labels[2],
+                                ("phase", phase),
+                            ],
+                            current,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn charger_metrics(
+    metrics: &mut Metrics,
+    labels: &[(&str, &str)],
+    state: &ChargerState,
+    session_kwh: Option<f64>,
+) {
+    let op_mode: u8 = state.charger_op_mode.into();
+
+    metrics.gauge(
+        "easee_charger_online",
+        "Whether the charger is online",
+        labels,
+        if state.is_online.unwrap_or(false) {
+            1.0
+        } else {
+            0.0
+        },
+    );
+    metrics.gauge(
+        "easee_charger_op_mode",
+        "Operating mode, 1 disconnected, 2 awaiting start, 3 charging, 4 completed, 5 error, 6 ready to charge",
+        labels,
+        op_mode.into(),
+    );
+
+    let values = [
+        (
+            "easee_charger_power_kilowatts",
+            "Current charging power",
+            state.total_power,
+        ),
+        (
+            "easee_charger_session_energy_kilowatt_hours",
+            "Energy charged in the ongoing session",
+            session_kwh.or(state.session_energy),
+        ),
+        (
+            "easee_charger_lifetime_energy_kilowatt_hours",
+            "Energy charged over the charger's lifetime",
+            state.lifetime_energy,
+        ),
+        (
+            "easee_charger_output_current_amperes",
+            "Current delivered to the car",
+            state.output_current,
+        ),
+        (
+            "easee_charger_dynamic_current_amperes",
+            "Dynamic current limit of the charger",
+            state.dynamic_charger_current,
+        ),
+    ];
+
+    for (name, help, value) in values {
+        if let Some(value) = value {
+            metrics.gauge(name, help, labels, value);
+        }
+    }
+
+    let terminals = [
+        ("t2", state.in_current_t2),
+        ("t3", state.in_current_t3),
+        ("t4", state.in_current_t4),
+        ("t5", state.in_current_t5),
+    ];
+    for (terminal, current) in terminals {
+        if let Some(current) = current {
+            let mut labels = labels.to_vec();
+            labels.push(("terminal", terminal));
+
+            metrics.gauge(
+                "easee_charger_input_current_amperes",
+                "Current on each input terminal",
+                &labels,
+                current,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charger_samples() {
+        let state = serde_json::from_str::<ChargerState>(
+            r#"{"chargerOpMode":3,"isOnline":true,"totalPower":7.2,"sessionEnergy":1.5,"inCurrentT3":10.5}"#,
+        )
+        .expect("state");
+
+        let mut metrics = Metrics::default();
+        charger_metrics(
+            &mut metrics,
+            &[("charger_id", "EC3VJ7GU")],
+            &state,
+            Some(2.25),
+        );
+        let rendered = metrics.render();
+
+        assert!(rendered.contains("easee_charger_online{charger_id=\"EC3VJ7GU\"} 1\n"));
+        assert!(rendered.contains("easee_charger_op_mode{charger_id=\"EC3VJ7GU\"} 3\n"));
+        assert!(rendered.contains("easee_charger_power_kilowatts{charger_id=\"EC3VJ7GU\"} 7.2\n"));
+        assert!(rendered.contains(
+            "easee_charger_session_energy_kilowatt_hours{charger_id=\"EC3VJ7GU\"} 2.25\n"
+        ));
+        assert!(rendered.contains(
+            "easee_charger_input_current_amperes{charger_id=\"EC3VJ7GU\",terminal=\"t3\"} 10.5\n"
+        ));
+        assert!(!rendered.contains("lifetime_energy"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Just enough of the Prometheus text exposition format.
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Gauge,
+    Counter,
+}
+
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    kind: Kind,
+    samples: Vec<(String, f64)>,
+}
+
+/// Samples grouped by metric name, rendered in the order the names were first seen
+#[derive(Default)]
+pub struct Metrics {
+    families: Vec<Family>,
+}
+
+impl Metrics {
+    pub fn gauge(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.sample(name, help, Kind::Gauge, labels, value);
+    }
+
+    pub fn counter(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.sample(name, help, Kind::Counter, labels, value);
+    }
+
+    fn sample(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        kind: Kind,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        let family = match self.families.iter().position(|f| f.name == name) {
+            Some(n) => &mut self.families[n],
+            None => {
+                self.families.push(Family {
+                    name,
+                    help,
+                    kind,
+                    samples: Vec::new(),
+                });
+                self.families.last_mut().expect("just pushed")
+            }
+        };
+
+        family.samples.push((render_labels(labels), value));
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        for family in &self.families {
+            let kind = match family.kind {
+                Kind::Gauge => "gauge",
+                Kind::Counter => "counter",
+            };
+
+            let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+            let _ = writeln!(out, "# TYPE {} {kind}", family.name);
+
+            for (labels, value) in &family.samples {
+                let _ = writeln!(out, "{}{labels} {value}", family.name);
+            }
+        }
+
+        out
+    }
+}
+
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+
+    let pairs = labels
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', r"\\")
+                .replace('"', "\\\"")
+                .replace('\n', r"\n");
+            format!("{name}=\"{value}\"")
+        })
+        .collect::<Vec<_>>();
+
+    format!("{{{}}}", pairs.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_families() {
+        let mut metrics = Metrics::default();
+        metrics.gauge("easee_up", "Whether it works", &[], 1.0);
+        metrics.counter(
+            "easee_errors_total",
+            "Errors",
+            &[("endpoint", "state")],
+            3.0,
+        );
+        metrics.gauge(
+            "easee_up",
+            "Whether it works",
+            &[("name", "Brf \"Ryssjan\"")],
+            0.0,
+        );
+
+        assert_eq!(
+            metrics.render(),
+            "# HELP easee_up Whether it works\n\
+             # TYPE easee_up gauge\n\
+             easee_up 1\n\
+             easee_up{name=\"Brf \\\"Ryssjan\\\"\"} 0\n\
+             # HELP easee_errors_total Errors\n\
+             # TYPE easee_errors_total counter\n\
+             easee_errors_total{endpoint=\"state\"} 3\n"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Short lived cache of successful GET replies, per account.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use easeeapi::RawResponse;
+
+pub struct ResponseCache {
+    ttl: Duration,
+    /// (account, path and query) -> (stored at, reply)
+    entries: HashMap<(String, String), (Instant, RawResponse)>,
+    /// Bumped by every invalidation of an account
+    generations: HashMap<String, u64>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+            generations: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, account: &str, path: &str, now: Instant) -> Option<&RawResponse> {
+        let (stored_at, reply) = self.entries.get(&(account.to_string(), path.to_string()))?;
+
+        (now.duration_since(*stored_at) < self.ttl).then_some(reply)
+    }
+
+    /// To pass to `insert`, taken before forwarding the request
+    pub fn generation(&self, account: &str) -> u64 {
+        self.generations.get(account).copied().unwrap_or(0)
+    }
+
+    /// Stores successful replies, dropping expired entries on the way. Replies to requests which
+    /// started before the account was last invalidated may be stale and aren't stored.
+    pub fn insert(
+        &mut self,
+        account: &str,
+        path: &str,
+        reply: RawResponse,
+        now: Instant,
+        generation: u64,
+    ) {
+        if self.ttl.is_zero()
+            || !reply.status.is_success()
+            || generation != self.generation(account)
+        {
+            return;
+        }
+
+        self.entries
+            .retain(|_, (stored_at, _)| now.duration_since(*stored_at) < self.ttl);
+        self.entries
+            .insert((account.to_string(), path.to_string()), (now, reply));
+    }
+
+    /// Forgets everything cached for an account, after a request which may have changed something
+    pub fn invalidate(&mut self, account: &str) {
+        self.entries.retain(|(cached, _), _| cached != account);
+        *self.generations.entry(account.to_string()).or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(status: u16, body: &'static str) -> RawResponse {
+        RawResponse {
+            status: http::StatusCode::from_u16(status).expect("status"),
+            content_type: Some("application/json".into()),
+            body: body.into(),
+        }
+    }
+
+    #[test]
+    fn expiry_and_invalidation() {
+        let start = Instant::now();
+        let mut cache = ResponseCache::new(Duration::from_secs(10));
+
+        cache.insert("home", "api/sites", reply(200, "[]"), start, 0);
+        cache.insert("home", "api/chargers/EC1/state", reply(404, ""), start, 0);
+        cache.insert("office", "api/sites", reply(200, "[1]"), start, 0);
+
+        let at = start + Duration::from_secs(5);
+        assert_eq!(
+            cache.get("home", "api/sites", at).map(|r| &r.body[..]),
+            Some(&b"[]"[..])
+        );
+        assert!(cache.get("home", "api/chargers/EC1/state", at).is_none());
+        assert!(cache.get("home", "api/sites?search=x", at).is_none());
+
+        assert!(
+            cache
+                .get("home", "api/sites", start + Duration::from_secs(10))
+                .is_none()
+        );
+
+        cache.invalidate("home");
+        assert!(cache.get("home", "api/sites", at).is_none());
+        assert!(cache.get("office", "api/sites", at).is_some());
+    }
+
+    #[test]
+    fn stale_replies_are_dropped() {
+        let now = Instant::now();
+        let mut cache = ResponseCache::new(Duration::from_secs(10));
+
+        // A GET is forwarded, then a POST finishes before it
+        let generation = cache.generation("home");
+        cache.invalidate("home");
+        cache.insert("home", "api/sites", reply(200, "[]"), now, generation);
+        assert!(cache.get("home", "api/sites", now).is_none());
+
+        cache.insert(
+            "home",
+            "api/sites",
+            reply(200, "[]"),
+            now,
+            cache.generation("home"),
+        );
+        assert!(cache.get("home", "api/sites", now).is_some());
+    }
+
+    #[test]
+    fn zero_ttl_disables() {
+        let now = Instant::now();
+        let mut cache = ResponseCache::new(Duration::ZERO);
+
+        cache.insert("home", "api/sites", reply(200, "[]"), now, 0);
+        assert!(cache.get("home", "api/sites", now).is_none());
+    }
+}
@@ -0,0 +1,283 @@
+//! Holds the sessions of one or more Easee accounts and proxies api requests for local services,
+//! so they don't log in and refresh on their own and invalidate each other's refresh tokens.
+//!
+//! A request to `/{account}/api/...` is forwarded to `https://api.easee.com/api/...` with the
+//! account's access token, and the reply is passed back as is. Successful GET replies are cached
+//! for `CACHE_TTL` seconds, any other method clears the account's cache. Requests of all services
+//! share one rate limit per account.
+//!
+//! The token endpoints under `api/accounts` are refused, a login or refresh by one service would
+//! end the gateway's session.
+//!
+//! Accounts are read from the json file at `ACCOUNTS`:
+//!
+//! ```json
+//! [
+//!     { "name": "home", "username": "+4712345678", "password": "..." },
+//!     { "name": "office", "accessToken": "...", "refreshToken": "..." }
+//! ]
+//! ```
+//!
+//! Tokens aren't saved when refreshed, so accounts given by tokens have to be updated after a
+//! restart; prefer username and password.
+//!
+//! Configured through the environment:
+//! * `ACCOUNTS`, path of the accounts file
+//! * `LISTEN` (127.0.0.1:9758)
+//! * `CACHE_TTL` in seconds (10), 0 disables caching
+//! * `RATE_LIMIT`, requests per minute per account (60)
+
+mod cache;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{RawQuery, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, Uri, header},
+    response::{IntoResponse, Response},
+    routing::any,
+};
+use easeeapi::{Client, RawResponse};
+use tracing::{info, warn};
+
+use cache::ResponseCache;
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountConfig {
+    name: String,
+    username: Option<String>,
+    password: Option<String>,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+}
+
+struct Gateway {
+    accounts: HashMap<String, Client>,
+    cache: Mutex<ResponseCache>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().init();
+
+    let var = |name, default: &str| std::env::var(name).unwrap_or_else(|_| default.into());
+    let accounts_path = std::env::var("ACCOUNTS").context("ACCOUNTS not set")?;
+    let listen = var("LISTEN", "127.0.0.1:9758");
+    let cache_ttl = Duration::from_secs(var("CACHE_TTL", "10").parse().context("CACHE_TTL")?);
+    let rate_limit = var("RATE_LIMIT", "60").parse().context("RATE_LIMIT")?;
+
+    let configs =
+        std::fs::read(&accounts_path).with_context(|| format!("reading {accounts_path}"))?;
+    let configs = serde_json::from_slice::<Vec<AccountConfig>>(&configs)
+        .with_context(|| format!("reading {accounts_path}"))?;
+
+    let mut accounts = HashMap::new();
+    for config in configs {
+        let client = connect(&config)
+            .await
+            .with_context(|| format!("account {}", config.name))?
+            .with_rate_limit(rate_limit, Duration::from_secs(60));
+
+        if accounts.insert(config.name.clone(), client).is_some() {
+            bail!("account {} configured twice", config.name);
+        }
+    }
+    info!(accounts = accounts.len(), "serving on http://{listen}");
+
+    let gateway = Arc::new(Gateway {
+        accounts,
+        cache: Mutex::new(ResponseCache::new(cache_ttl)),
+    });
+
+    let app = Router::new()
+        .route("/{account}/{*path}", any(proxy))
+        .with_state(gateway);
+
+    let listener = tokio::net::TcpListener::bind(&listen)
+        .await
+        .with_context(|| format!("binding {listen}"))?;
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    Ok(())
+}
+
+async fn connect(config: &AccountConfig) -> Result<Client> {
+    match config {
+        AccountConfig {
+            username: Some(username),
+            password: Some(password),
+            ..
+        } => Ok(Client::login(username, password).await?),
+        AccountConfig {
+            access_token: Some(access_token),
+            refresh_token: Some(refresh_token),
+            ..
+        } => Ok(Client::new(access_token.clone(), refresh_token.clone())?),
+        _ => bail!("needs either username and password, or accessToken and refreshToken"),
+    }
+}
+
+/// Endpoints under `api/accounts` which would replace or end the gateway's session
+const TOKEN_ENDPOINTS: &[&str] = &["login", "refresh_token", "logout", "change_password"];
+
+/// Splits `/{account}/{path}` into the decoded account and the path, still percent-encoded so
+/// it's forwarded exactly as received
+fn split_path(path: &str) -> Option<(String, &str)> {
+    let (account, path) = path.trim_start_matches('/').split_once('/')?;
+    let account = percent_encoding::percent_decode_str(account)
+        .decode_utf8()
+        .ok()?;
+
+    Some((account.into_owned(), path))
+}
+
+fn is_token_endpoint(path: &str) -> bool {
+    let path = percent_encoding::percent_decode_str(path)
+        .decode_utf8_lossy()
+        .to_ascii_lowercase();
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    matches!(
+        segments[..],
+        ["api", "accounts", endpoint, ..] if TOKEN_ENDPOINTS.contains(&endpoint)
+    )
+}
+
+/// The path and query forwarded to the api
+fn target(path: &str, query: Option<&str>) -> String {
+    match query {
+        Some(query) if !query.is_empty() => format!("{path}?{query}"),
+        _ => path.to_string(),
+    }
+}
+
+async fn proxy(
+    State(gateway): State<Arc<Gateway>>,
+    uri: Uri,
+    RawQuery(query): RawQuery,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some((account, path)) = split_path(uri.path()) else {
+        return (StatusCode::NOT_FOUND, "expected /{account}/{path}").into_response();
+    };
+    let Some(client) = gateway.accounts.get(&account) else {
+        return (StatusCode::NOT_FOUND, format!("unknown account {account}")).into_response();
+    };
+    if is_token_endpoint(path) {
+        return (
+            StatusCode::FORBIDDEN,
+            "the gateway owns this account's session",
+        )
+            .into_response();
+    }
+
+    let target = target(path, query.as_deref());
+    let cacheable = method == Method::GET;
+
+    let generation = {
+        let cache = gateway.cache.lock().expect("cache lock");
+        if cacheable && let Some(reply) = cache.get(&account, &target, Instant::now()) {
+            return respond(reply.clone(), "hit");
+        }
+
+        cache.generation(&account)
+    };
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+
+    let reply = match client
+        .raw_request(method.clone(), &target, content_type, body)
+        .await
+    {
+        Ok(reply) => reply,
+        Err(err) => {
+            warn!(account, "{method} {target} failed: {err}");
+            return (StatusCode::BAD_GATEWAY, err.to_string()).into_response();
+        }
+    };
+
+    let mut cache = gateway.cache.lock().expect("cache lock");
+    if cacheable {
+        cache.insert(&account, &target, reply.clone(), Instant::now(), generation);
+    } else {
+        cache.invalidate(&account);
+    }
+
+    respond(reply, "miss")
+}
+
+fn respond(reply: RawResponse, cache: &'static str) -> Response {
+    let mut response = (reply.status, reply.body).into_response();
+
+    let headers = response.headers_mut();
+    if let Some(content_type) = reply
+        .content_type
+        .and_then(|v| HeaderValue::from_str(&v).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert("x-cache", HeaderValue::from_static(cache));
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accounts_and_targets() {
+        let configs = serde_json::from_str::<Vec<AccountConfig>>(
+            r#"[
+                { "name": "home", "username": "+4712345678", "password": "secret" },
+                { "name": "office", "accessToken": "a", "refreshToken": "r" }
+            ]"#,
+        )
+        .expect("parse");
+
+        assert_eq!(configs[0].username.as_deref(), Some("+4712345678"));
+        assert_eq!(configs[1].refresh_token.as_deref(), Some("r"));
+
+        assert_eq!(target("api/sites", None), "api/sites");
+        assert_eq!(target("api/sites", Some("")), "api/sites");
+        assert_eq!(
+            target("api/sessions/charger/EC1/sessions", Some("from=2024-01-01")),
+            "api/sessions/charger/EC1/sessions?from=2024-01-01"
+        );
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            split_path("/my%20home/api/chargers/EC1%2F2/state"),
+            Some(("my home".to_string(), "api/chargers/EC1%2F2/state"))
+        );
+        assert_eq!(split_path("/home"), None);
+
+        assert!(is_token_endpoint("api/accounts/refresh_token"));
+        assert!(is_token_endpoint("api/Accounts/Login/"));
+        assert!(is_token_endpoint("api//accounts/%6cogin"));
+        assert!(!is_token_endpoint("api/accounts/profile"));
+        assert!(!is_token_endpoint("api/sites"));
+    }
+}
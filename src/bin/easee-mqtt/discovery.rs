@@ -0,0 +1,246 @@
+//! Topic layout and Home Assistant discovery config.
+//!
+//! Everything for a charger lives under `{prefix}/{charger_id}/`:
+//! * `state` - `ChargerState` as json
+//! * `session` - `{"kwh": ..}` of the ongoing session, `null` kWh without one
+//! * `availability` - `online` / `offline`
+//! * `command/{name}` - published to by Home Assistant, eg `command/start_charging`
+//!
+//! Sites are published to `{prefix}/site/{site_id}`.
+
+use easeeapi::requests::ChargerCommand;
+use serde_json::{Value, json};
+
+#[derive(Clone)]
+pub struct Topics {
+    pub prefix: String,
+    pub discovery_prefix: String,
+}
+
+impl Topics {
+    pub fn bridge_status(&self) -> String {
+        format!("{}/bridge/status", self.prefix)
+    }
+
+    pub fn state(&self, charger_id: &str) -> String {
+        format!("{}/{charger_id}/state", self.prefix)
+    }
+
+    pub fn session(&self, charger_id: &str) -> String {
+        format!("{}/{charger_id}/session", self.prefix)
+    }
+
+    pub fn availability(&self, charger_id: &str) -> String {
+        format!("{}/{charger_id}/availability", self.prefix)
+    }
+
+    pub fn command(&self, charger_id: &str, command: ChargerCommand) -> String {
+        format!("{}/{charger_id}/command/{}", self.prefix, command.name())
+    }
+
+    pub fn site(&self, site_id: impl std::fmt::Display) -> String {
+        format!("{}/site/{site_id}", self.prefix)
+    }
+
+    /// What to subscribe to for commands to any charger
+    pub fn command_filter(&self) -> String {
+        format!("{}/+/command/+", self.prefix)
+    }
+
+    /// Returns the charger id and command if `topic` is a command topic
+    pub fn parse_command(&self, topic: &str) -> Option<(String, ChargerCommand)> {
+        let rest = topic.strip_prefix(&self.prefix)?.strip_prefix('/')?;
+
+        match rest.split('/').collect::<Vec<_>>()[..] {
+            [charger_id, "command", command] if !charger_id.is_empty() => {
+                Some((charger_id.to_string(), command.parse().ok()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Retained discovery configs as `(topic, payload)` for a charger's entities
+    pub fn discovery(&self, charger_id: &str, name: &str) -> Vec<(String, Value)> {
+        let device = json!({
+            "identifiers": [format!("easee_{charger_id}")],
+            "name": name,
+            "manufacturer": "Easee",
+            "serial_number": charger_id,
+        });
+
+        let entity = |component: &str, object: &str, mut config: Value| {
+            let unique_id = format!("easee_{charger_id}_{object}");
+            let topic = format!("{}/{component}/{unique_id}/config", self.discovery_prefix);
+
+            let fields = config.as_object_mut().expect("config object");
+            fields.insert("unique_id".into(), unique_id.clone().into());
+            fields.insert("object_id".into(), unique_id.into());
+            fields.insert("device".into(), device.clone());
+            fields.insert(
+                "availability_topic".into(),
+                self.availability(charger_id).into(),
+            );
+
+            (topic, config)
+        };
+
+        let state = self.state(charger_id);
+        let sensor =
+            |object: &str, name: &str, field: &str, unit: &str, class: &str, state_class: &str| {
+                entity(
+                    "sensor",
+                    object,
+                    json!({
+                        "name": name,
+                        "state_topic": state,
+                        "value_template": format!("{{{{ value_json.{field} }}}}"),
+                        "unit_of_measurement": unit,
+                        "device_class": class,
+                        "state_class": state_class,
+                    }),
+                )
+            };
+
+        let mut configs = vec![
+            sensor("power", "Power", "totalPower", "kW", "power", "measurement"),
+            sensor(
+                "output_current",
+                "Output current",
+                "outputCurrent",
+                "A",
+                "current",
+                "measurement",
+            ),
+            sensor(
+                "lifetime_energy",
+                "Lifetime energy",
+                "lifetimeEnergy",
+                "kWh",
+                "energy",
+                "total_increasing",
+            ),
+            sensor(
+                "wifi_rssi",
+                "Wi-Fi signal",
+                "wiFiRSSI",
+                "dBm",
+                "signal_strength",
+                "measurement",
+            ),
+            entity(
+                "sensor",
+                "session_energy",
+                json!({
+                    "name": "Session energy",
+                    "state_topic": self.session(charger_id),
+                    "value_template": "{{ value_json.kwh }}",
+                    "unit_of_measurement": "kWh",
+                    "device_class": "energy",
+                    "state_class": "total_increasing",
+                }),
+            ),
+            entity(
+                "sensor",
+                "status",
+                json!({
+                    "name": "Status",
+                    "state_topic": state,
+                    "value_template": OP_MODE_TEMPLATE,
+                    "device_class": "enum",
+                    "options": OP_MODES,
+                }),
+            ),
+            entity(
+                "binary_sensor",
+                "cable_locked",
+                json!({
+                    "name": "Cable locked",
+                    "state_topic": state,
+                    "value_template": "{{ 'ON' if value_json.cableLocked else 'OFF' }}",
+                    "device_class": "lock",
+                    // Home Assistant's lock class is `on` when unlocked
+                    "payload_on": "OFF",
+                    "payload_off": "ON",
+                }),
+            ),
+        ];
+
+        configs.extend(ChargerCommand::ALL.into_iter().map(|command| {
+            let object = command.name();
+            let name = object[..1].to_uppercase() + &object[1..].replace('_', " ");
+
+            entity(
+                "button",
+                object,
+                json!({
+                    "name": name,
+                    "command_topic": self.command(charger_id, command),
+                }),
+            )
+        }));
+
+        configs
+    }
+}
+
+/// `ChargerOpMode` as numbered by the api
+const OP_MODES: [&str; 9] = [
+    "offline",
+    "disconnected",
+    "awaiting_start",
+    "charging",
+    "completed",
+    "error",
+    "ready_to_charge",
+    "awaiting_authentication",
+    "deauthenticating",
+];
+
+const OP_MODE_TEMPLATE: &str = "{% set modes = ['offline', 'disconnected', 'awaiting_start', \
+    'charging', 'completed', 'error', 'ready_to_charge', 'awaiting_authentication', \
+    'deauthenticating'] %}{{ modes[value_json.chargerOpMode] | default('unknown') }}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topics() -> Topics {
+        Topics {
+            prefix: "easee".into(),
+            discovery_prefix: "homeassistant".into(),
+        }
+    }
+
+    #[test]
+    fn command_topics() {
+        let topics = topics();
+
+        let topic = topics.command("EC3VJ7GU", ChargerCommand::PauseCharging);
+        assert_eq!(topic, "easee/EC3VJ7GU/command/pause_charging");
+        assert_eq!(
+            topics.parse_command(&topic),
+            Some(("EC3VJ7GU".into(), ChargerCommand::PauseCharging))
+        );
+
+        assert_eq!(topics.parse_command("easee/EC3VJ7GU/command/explode"), None);
+        assert_eq!(topics.parse_command("easee/EC3VJ7GU/state"), None);
+        assert_eq!(topics.parse_command("easeee/EC3VJ7GU/command/reboot"), None);
+    }
+
+    #[test]
+    fn discovery_configs() {
+        let configs = topics().discovery("EC3VJ7GU", "Garage");
+
+        let (topic, power) = &configs[0];
+        assert_eq!(topic, "homeassistant/sensor/easee_EC3VJ7GU_power/config");
+        assert_eq!(power["state_topic"], "easee/EC3VJ7GU/state");
+        assert_eq!(power["value_template"], "{{ value_json.totalPower }}");
+        assert_eq!(power["device"]["name"], "Garage");
+
+        let buttons = configs
+            .iter()
+            .filter(|(topic, _)| topic.contains("/button/"))
+            .count();
+        assert_eq!(buttons, ChargerCommand::ALL.len());
+    }
+}
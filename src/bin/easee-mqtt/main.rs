@@ -0,0 +1,410 @@
+//! Bridges Easee chargers to an MQTT broker, with Home Assistant discovery.
+//!
+//! Configured through the environment:
+//! * `EASEE_ACCESS_TOKEN`, `EASEE_REFRESH_TOKEN` - see `Client::from_env`
+//! * `MQTT_HOST` (localhost), `MQTT_PORT` (1883), `MQTT_USERNAME`, `MQTT_PASSWORD`
+//! * `MQTT_CLIENT_ID` (easee-mqtt), must be unique when several bridges share a broker
+//! * `MQTT_PREFIX` (easee), `HA_DISCOVERY_PREFIX` (homeassistant)
+//! * `POLL_INTERVAL` in seconds (30)
+//! * `SITES_INTERVAL` in seconds (3600), how often sites and chargers are looked up again
+//!
+//! To try it against a local broker:
+//!
+//! ```sh
+//! mosquitto -p 1883 &
+//! mosquitto_sub -v -t 'easee/#' &
+//! cargo run --features mqtt --bin easee-mqtt
+//! mosquitto_pub -t easee/EC3VJ7GU/command/pause_charging -n
+//! ```
+
+mod discovery;
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use easeeapi::{
+    Client, OptionalResult, Site, SiteId,
+    requests::{
+        ChargerCommand, GetChargerState, GetOngoingSession, GetSite, GetSites, SendChargerCommand,
+    },
+};
+use rumqttc::{AsyncClient, Event, EventLoop, LastWill, MqttOptions, Packet, QoS};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use discovery::Topics;
+
+struct Config {
+    host: String,
+    port: u16,
+    client_id: String,
+    credentials: Option<(String, String)>,
+    topics: Topics,
+    poll_interval: Duration,
+    sites_interval: Duration,
+}
+
+impl Config {
+    fn from_env() -> Result<Self> {
+        let var = |name, default: &str| std::env::var(name).unwrap_or_else(|_| default.into());
+
+        let credentials = match (
+            std::env::var("MQTT_USERNAME"),
+            std::env::var("MQTT_PASSWORD"),
+        ) {
+            (Ok(username), Ok(password)) => Some((username, password)),
+            _ => None,
+        };
+
+        Ok(Self {
+            host: var("MQTT_HOST", "localhost"),
+            port: var("MQTT_PORT", "1883").parse().context("MQTT_PORT")?,
+            client_id: var("MQTT_CLIENT_ID", "easee-mqtt"),
+            credentials,
+            topics: Topics {
+                prefix: var("MQTT_PREFIX", "easee"),
+                discovery_prefix: var("HA_DISCOVERY_PREFIX", "homeassistant"),
+            },
+            poll_interval: Duration::from_secs(
+                var("POLL_INTERVAL", "30")
+                    .parse()
+                    .context("POLL_INTERVAL")?,
+            ),
+            sites_interval: Duration::from_secs(
+                var("SITES_INTERVAL", "3600")
+                    .parse()
+                    .context("SITES_INTERVAL")?,
+            ),
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().init();
+
+    let config = Config::from_env()?;
+    let client = Client::from_env()?;
+
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(
+        config.topics.bridge_status(),
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if let Some((username, password)) = &config.credentials {
+        options.set_credentials(username, password);
+    }
+
+    let (mqtt, eventloop) = AsyncClient::new(options, 64);
+    let (commands_tx, mut commands) = mpsc::channel(16);
+
+    tokio::spawn(drive_eventloop(
+        eventloop,
+        mqtt.clone(),
+        config.topics.clone(),
+        commands_tx,
+    ));
+
+    mqtt.publish(
+        config.topics.bridge_status(),
+        QoS::AtLeastOnce,
+        true,
+        "online",
+    )
+    .await?;
+
+    // As of the last successful lookup
+    let mut chargers = Vec::new();
+    let mut sites_ticker = tokio::time::interval(config.sites_interval);
+
+    let mut ticker = tokio::time::interval(config.poll_interval);
+    loop {
+        tokio::select! {
+            // Listed first, so the chargers are known before the first poll
+            biased;
+
+            _ = sites_ticker.tick() => {
+                match refresh_sites(&client, &mqtt, &config.topics, &chargers).await {
+                    Ok(found) => chargers = found,
+                    Err(err) => warn!("looking up sites failed, keeping the known chargers: {err:#}"),
+                }
+            }
+
+            _ = ticker.tick() => {
+                for charger in &chargers {
+                    if let Err(err) = publish_charger(&client, &mqtt, &config.topics, &charger.id).await {
+                        warn!(charger_id = charger.id, "publishing charger failed: {err:#}");
+                    }
+                }
+            }
+
+            Some((charger_id, command)) = commands.recv() => {
+                if !chargers.iter().any(|charger| charger.id == charger_id) {
+                    warn!(charger_id, "ignoring command for a charger which isn't bridged");
+                    continue;
+                }
+
+                run_command(&client, &charger_id, command).await;
+                // Show the effect without waiting for the next poll
+                ticker.reset_after(Duration::from_secs(5));
+            }
+
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    mqtt.publish(
+        config.topics.bridge_status(),
+        QoS::AtLeastOnce,
+        true,
+        "offline",
+    )
+    .await?;
+    mqtt.disconnect().await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct KnownCharger {
+    site_id: SiteId,
+    id: String,
+    name: String,
+}
+
+/// Fetches every site, returning the ids of the sites that failed separately
+async fn fetch_sites(client: &Client) -> Result<(Vec<Site>, Vec<SiteId>)> {
+    let mut sites = Vec::new();
+    let mut failed = Vec::new();
+
+    let mut pages = GetSites::default().pages();
+    while let Some(site) = pages.next(client).await? {
+        match GetSite(site.id).send(client).await {
+            Ok(site) => sites.push(site),
+            Err(err) => {
+                warn!(site_id = %site.id, "fetching site failed, skipping: {err}");
+                failed.push(site.id);
+            }
+        }
+    }
+
+    Ok((sites, failed))
+}
+
+/// Publishes sites and discovery for their chargers, removing discovery of chargers which are
+/// gone. Returns the chargers to poll.
+async fn refresh_sites(
+    client: &Client,
+    mqtt: &AsyncClient,
+    topics: &Topics,
+    known: &[KnownCharger],
+) -> Result<Vec<KnownCharger>> {
+    let (sites, failed) = fetch_sites(client).await?;
+    let found = sites
+        .iter()
+        .flat_map(|site| {
+            site.circuits
+                .iter()
+                .flat_map(|c| &c.chargers)
+                .map(|charger| KnownCharger {
+                    site_id: site.id,
+                    id: charger.id.clone(),
+                    name: charger.name.clone(),
+                })
+        })
+        .collect::<Vec<_>>();
+
+    for site in &sites {
+        let payload = serde_json::to_vec(site)?;
+        mqtt.publish(topics.site(site.id), QoS::AtLeastOnce, true, payload)
+            .await?;
+    }
+
+    for charger in &found {
+        for (topic, payload) in topics.discovery(&charger.id, &charger.name) {
+            mqtt.publish(topic, QoS::AtLeastOnce, true, serde_json::to_vec(&payload)?)
+                .await?;
+        }
+    }
+
+    let (chargers, gone) = merge(known, found, &failed);
+    for charger in gone {
+        info!(charger_id = charger.id, "charger gone, removing it");
+        for (topic, _) in topics.discovery(&charger.id, &charger.name) {
+            mqtt.publish(topic, QoS::AtLeastOnce, true, Vec::new())
+                .await?;
+        }
+    }
+
+    info!(
+        sites = sites.len(),
+        failed_sites = failed.len(),
+        chargers = chargers.len(),
+        "bridging"
+    );
+    Ok(chargers)
+}
+
+/// The chargers to poll and the ones which are gone. Chargers on sites which failed to load
+/// are kept as they were.
+fn merge(
+    known: &[KnownCharger],
+    mut found: Vec<KnownCharger>,
+    failed: &[SiteId],
+) -> (Vec<KnownCharger>, Vec<KnownCharger>) {
+    let mut gone = Vec::new();
+
+    for charger in known {
+        if found.iter().any(|f| f.id == charger.id) {
+            continue;
+        }
+
+        if failed.contains(&charger.site_id) {
+            found.push(charger.clone());
+        } else {
+            gone.push(charger.clone());
+        }
+    }
+
+    (found, gone)
+}
+
+async fn publish_charger(
+    client: &Client,
+    mqtt: &AsyncClient,
+    topics: &Topics,
+    charger_id: &str,
+) -> Result<()> {
+    let state = GetChargerState::new(charger_id).send(client).await?;
+    let session = GetOngoingSession::new(charger_id)
+        .send(client)
+        .await
+        .optional()?;
+
+    let availability = if state.is_online.unwrap_or(false) {
+        "online"
+    } else {
+        "offline"
+    };
+    let session = serde_json::json!({
+        "kwh": session.map(|s| s.kilo_watt_hours),
+    });
+
+    mqtt.publish(
+        topics.availability(charger_id),
+        QoS::AtLeastOnce,
+        true,
+        availability,
+    )
+    .await?;
+    mqtt.publish(
+        topics.state(charger_id),
+        QoS::AtLeastOnce,
+        true,
+        serde_json::to_vec(&state)?,
+    )
+    .await?;
+    mqtt.publish(
+        topics.session(charger_id),
+        QoS::AtLeastOnce,
+        true,
+        serde_json::to_vec(&session)?,
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn run_command(client: &Client, charger_id: &str, command: ChargerCommand) {
+    info!(charger_id, command = command.name(), "sending command");
+
+    if let Err(err) = SendChargerCommand::new(charger_id, command)
+        .send(client)
+        .await
+    {
+        warn!(
+            charger_id,
+            command = command.name(),
+            "command failed: {err}"
+        );
+    }
+}
+
+/// Keeps the connection alive, resubscribing after every reconnect, and forwards commands
+async fn drive_eventloop(
+    mut eventloop: EventLoop,
+    mqtt: AsyncClient,
+    topics: Topics,
+    commands: mpsc::Sender<(String, ChargerCommand)>,
+) {
+    let command_filter = topics.command_filter();
+
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                info!("connected to broker");
+                if let Err(err) = mqtt.subscribe(&command_filter, QoS::AtLeastOnce).await {
+                    warn!("subscribing to commands failed: {err}");
+                }
+            }
+
+            // Retained commands are delivered again after every reconnect, so running them
+            // would reboot or stop the charger each time
+            Ok(Event::Incoming(Packet::Publish(publish))) if publish.retain => {
+                warn!(topic = publish.topic, "ignoring retained command");
+            }
+
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                let Some(command) = topics.parse_command(&publish.topic) else {
+                    warn!(topic = publish.topic, "ignoring unknown command");
+                    continue;
+                };
+
+                if commands.send(command).await.is_err() {
+                    return;
+                }
+            }
+
+            Ok(_) => {}
+
+            Err(err) => {
+                warn!("mqtt connection: {err}");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn charger(site_id: i64, id: &str) -> KnownCharger {
+        KnownCharger {
+            site_id: SiteId(site_id),
+            id: id.into(),
+            name: id.into(),
+        }
+    }
+
+    #[test]
+    fn merging_chargers() {
+        let known = [charger(1, "EC1"), charger(1, "EC2"), charger(2, "EC3")];
+
+        // EC2 was removed from site 1, site 2 failed to load
+        let (chargers, gone) = merge(
+            &known,
+            vec![charger(1, "EC1"), charger(1, "EC4")],
+            &[SiteId(2)],
+        );
+
+        assert_eq!(
+            chargers,
+            [charger(1, "EC1"), charger(1, "EC4"), charger(2, "EC3")]
+        );
+        assert_eq!(gone, [charger(1, "EC2")]);
+    }
+}
@@ -0,0 +1,749 @@
+//! One Easee charger presented as an OCPP 1.6J charge point with a single connector.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use easeeapi::{
+    ChargerOpMode, ChargerSession, ChargerState, Client, DateTime, OptionalResult,
+    requests::{ChargerCommand, GetChargerState, GetOngoingSession, SendChargerCommand},
+};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::{net::TcpStream, time::Instant};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream,
+    tungstenite::{self, client::IntoClientRequest, http::HeaderValue},
+};
+use tracing::{debug, info, warn};
+
+use crate::messages::Frame;
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const CONNECTOR_ID: u8 = 1;
+const CALL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Sent as `idTag` for sessions that weren't started with a token
+const DEFAULT_ID_TAG: &str = "EASEE";
+/// How long the `idTag` of a remote start is kept waiting for the session it starts
+const REMOTE_START_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The configuration keys the CSMS can change
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub heartbeat_interval: Duration,
+    pub meter_value_sample_interval: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: Duration::from_secs(300),
+            meter_value_sample_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// What to do about a call from the CSMS
+#[derive(Debug, Clone, PartialEq)]
+enum Reply {
+    Respond(Value),
+    /// Send the command, then respond `Accepted` if the charger took it, `Rejected` otherwise
+    Command(ChargerCommand),
+    Error {
+        code: &'static str,
+        description: String,
+    },
+}
+
+/// `transaction_id` is the ongoing transaction, if any
+fn handle_call(
+    action: &str,
+    payload: &Value,
+    settings: &mut Settings,
+    transaction_id: Option<i64>,
+) -> Reply {
+    let rejected = || Reply::Respond(json!({ "status": "Rejected" }));
+
+    match action {
+        // Starts charging a connected car, the session shows up on the next poll and its
+        // transaction is started with the requested `idTag`
+        "RemoteStartTransaction" => {
+            let connector = payload["connectorId"].as_u64();
+            if transaction_id.is_some()
+                || connector.is_some_and(|c| c != u64::from(CONNECTOR_ID))
+                || payload["idTag"].as_str().is_none_or(str::is_empty)
+            {
+                return rejected();
+            }
+
+            Reply::Command(ChargerCommand::StartCharging)
+        }
+        "RemoteStopTransaction" => {
+            if transaction_id.is_none() || payload["transactionId"].as_i64() != transaction_id {
+                return rejected();
+            }
+
+            Reply::Command(ChargerCommand::StopCharging)
+        }
+        // Easee chargers only know one way to reset. Transactions have to be stopped before a
+        // reset, which the charger won't do, so it's left to the CSMS.
+        "Reset" if transaction_id.is_some() => rejected(),
+        "Reset" => Reply::Command(ChargerCommand::Reboot),
+
+        "ChangeConfiguration" => {
+            let key = payload["key"].as_str().unwrap_or_default();
+            let seconds = payload["value"]
+                .as_str()
+                .and_then(|value| value.parse::<u64>().ok());
+
+            let status = match (key, seconds) {
+                ("HeartbeatInterval", Some(seconds)) => {
+                    settings.heartbeat_interval = Duration::from_secs(seconds.max(1));
+                    "Accepted"
+                }
+                ("MeterValueSampleInterval", Some(seconds)) => {
+                    settings.meter_value_sample_interval = Duration::from_secs(seconds.max(1));
+                    "Accepted"
+                }
+                ("HeartbeatInterval" | "MeterValueSampleInterval", None) => "Rejected",
+                _ => "NotSupported",
+            };
+
+            Reply::Respond(json!({ "status": status }))
+        }
+
+        "GetConfiguration" => Reply::Respond(json!({
+            "configurationKey": [
+                {
+                    "key": "HeartbeatInterval",
+                    "readonly": false,
+                    "value": settings.heartbeat_interval.as_secs().to_string(),
+                },
+                {
+                    "key": "MeterValueSampleInterval",
+                    "readonly": false,
+                    "value": settings.meter_value_sample_interval.as_secs().to_string(),
+                },
+            ],
+        })),
+
+        action => Reply::Error {
+            code: "NotImplemented",
+            description: format!("{action} is not supported"),
+        },
+    }
+}
+
+/// `status` and `errorCode` for a StatusNotification
+fn status(state: &ChargerState) -> (&'static str, &'static str) {
+    if state.is_online == Some(false) {
+        return ("Unavailable", "NoError");
+    }
+
+    match state.charger_op_mode {
+        ChargerOpMode::Disconnected => ("Available", "NoError"),
+        ChargerOpMode::AwaitingStart | ChargerOpMode::AwaitingAuthentication => {
+            ("Preparing", "NoError")
+        }
+        ChargerOpMode::Charging => ("Charging", "NoError"),
+        // The car is full or paused itself
+        ChargerOpMode::Completed => ("SuspendedEV", "NoError"),
+        ChargerOpMode::ReadyToCharge => ("SuspendedEVSE", "NoError"),
+        ChargerOpMode::Deauthenticating => ("Finishing", "NoError"),
+        ChargerOpMode::Error => ("Faulted", "OtherError"),
+        ChargerOpMode::Offline | ChargerOpMode::Unknown(_) => ("Unavailable", "NoError"),
+    }
+}
+
+/// The `reason` of a StopTransaction, `session` being the one that follows it
+fn stop_reason(session: Option<&ChargerSession>) -> &'static str {
+    match session {
+        None => "EVDisconnected",
+        // Replaced without seeing the car leave
+        Some(_) => "Other",
+    }
+}
+
+/// The `idTag` of a StartTransaction, preferring the token the session was authorized with
+fn id_tag<'a>(session: &'a ChargerSession, remote_start: Option<&'a (String, Instant)>) -> &'a str {
+    let remote = remote_start
+        .filter(|(_, requested)| requested.elapsed() < REMOTE_START_TIMEOUT)
+        .map(|(id_tag, _)| id_tag.as_str());
+
+    session
+        .auth_token
+        .as_deref()
+        .or(remote)
+        .unwrap_or(DEFAULT_ID_TAG)
+}
+
+/// The lifetime energy register in Wh, which OCPP meter values are read from
+fn meter_wh(state: &ChargerState) -> i64 {
+    (state.lifetime_energy.unwrap_or(0.0) * 1000.0).round() as i64
+}
+
+/// The CSMS transaction of an ongoing session. Outlives the connection, so a reconnect during a
+/// session doesn't start the transaction again.
+pub struct Transaction {
+    session_id: i64,
+    transaction_id: i64,
+}
+
+/// Transactions to stop and start for the current ongoing session
+fn transaction_changes(
+    current: Option<&Transaction>,
+    session: Option<&ChargerSession>,
+) -> (bool, bool) {
+    match (current, session) {
+        (Some(tx), Some(session)) if tx.session_id == session.id => (false, false),
+        (current, session) => (current.is_some(), session.is_some()),
+    }
+}
+
+pub struct ChargePoint {
+    client: Client,
+    charger_id: String,
+    ws: WebSocket,
+    settings: Settings,
+    next_id: u64,
+    status: Option<(&'static str, &'static str)>,
+    /// The id of `run`'s transaction, for calls from the CSMS about it
+    transaction_id: Option<i64>,
+    /// The `idTag` of an accepted remote start, and when it was requested
+    remote_start: Option<(String, Instant)>,
+    last_meter_values: Option<Instant>,
+}
+
+impl ChargePoint {
+    /// Connects to `{csms_url}/{charger_id}` and sends the BootNotification
+    pub async fn connect(client: Client, charger_id: String, csms_url: &str) -> Result<Self> {
+        let url = format!("{}/{charger_id}", csms_url.trim_end_matches('/'));
+
+        let mut request = url.as_str().into_client_request()?;
+        request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            HeaderValue::from_static("ocpp1.6"),
+        );
+
+        let (ws, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .with_context(|| format!("connecting to {url}"))?;
+
+        let mut charge_point = Self {
+            client,
+            charger_id,
+            ws,
+            settings: Settings::default(),
+            next_id: 0,
+            status: None,
+            transaction_id: None,
+            remote_start: None,
+            last_meter_values: None,
+        };
+
+        charge_point.boot().await?;
+        Ok(charge_point)
+    }
+
+    async fn boot(&mut self) -> Result<()> {
+        let state = GetChargerState::new(&self.charger_id)
+            .send(&self.client)
+            .await?;
+
+        let mut boot = json!({
+            "chargePointVendor": "Easee",
+            "chargePointModel": "Easee",
+            "chargePointSerialNumber": self.charger_id,
+        });
+        if let Some(firmware) = state.charger_firmware {
+            boot["firmwareVersion"] = firmware.to_string().into();
+        }
+
+        loop {
+            let res = self.call("BootNotification", boot.clone()).await?;
+
+            if let Some(interval) = res["interval"].as_u64().filter(|n| *n > 0) {
+                self.settings.heartbeat_interval = Duration::from_secs(interval);
+            }
+
+            match res["status"].as_str() {
+                Some("Accepted") => break,
+                Some("Pending") => {
+                    info!(charger_id = self.charger_id, "boot pending, retrying");
+                    tokio::time::sleep(self.settings.heartbeat_interval).await;
+                }
+                status => bail!("boot notification not accepted: {status:?}"),
+            }
+        }
+
+        info!(charger_id = self.charger_id, "registered with csms");
+        Ok(())
+    }
+
+    /// Runs until the connection is lost, keeping `transaction` up to date
+    pub async fn run(
+        mut self,
+        poll_interval: Duration,
+        transaction: &mut Option<Transaction>,
+    ) -> Result<()> {
+        self.transaction_id = transaction.as_ref().map(|tx| tx.transaction_id);
+        let mut poll = tokio::time::interval(poll_interval);
+        let mut heartbeat = tokio::time::interval(self.settings.heartbeat_interval);
+        heartbeat.reset();
+
+        loop {
+            tokio::select! {
+                _ = poll.tick() => match self.fetch().await {
+                    Ok((state, session)) => self.sync(&state, session.as_ref(), transaction).await?,
+                    // Keep the csms connection, the next poll will catch up
+                    Err(err) => warn!(charger_id = self.charger_id, "polling failed: {err}"),
+                },
+
+                _ = heartbeat.tick() => {
+                    self.call("Heartbeat", json!({})).await?;
+                }
+
+                frame = self.ws.next() => {
+                    let text = match frame {
+                        Some(Ok(tungstenite::Message::Text(text))) => text,
+                        Some(Ok(tungstenite::Message::Close(_))) | None => bail!("closed by csms"),
+                        Some(Ok(_)) => continue,
+                        Some(Err(err)) => return Err(err.into()),
+                    };
+
+                    let heartbeat_interval = self.settings.heartbeat_interval;
+                    self.handle_frame(&text).await?;
+
+                    if self.settings.heartbeat_interval != heartbeat_interval {
+                        heartbeat = tokio::time::interval(self.settings.heartbeat_interval);
+                        heartbeat.reset();
+                    }
+                }
+            }
+        }
+    }
+
+    async fn fetch(&self) -> easeeapi::Result<(ChargerState, Option<ChargerSession>)> {
+        let state = GetChargerState::new(&self.charger_id)
+            .send(&self.client)
+            .await?;
+        let session = GetOngoingSession::new(&self.charger_id)
+            .send(&self.client)
+            .await
+            .optional()?;
+
+        Ok((state, session))
+    }
+
+    /// Reports status, transaction and meter value changes since the last poll
+    async fn sync(
+        &mut self,
+        state: &ChargerState,
+        session: Option<&ChargerSession>,
+        transaction: &mut Option<Transaction>,
+    ) -> Result<()> {
+        let now = DateTime::now_utc().format_zulu();
+        let meter = meter_wh(state);
+
+        let (stop, start) = transaction_changes(transaction.as_ref(), session);
+
+        if stop && let Some(tx) = transaction {
+            self.call(
+                "StopTransaction",
+                json!({
+                    "transactionId": tx.transaction_id,
+                    "meterStop": meter,
+                    "timestamp": now,
+                    "reason": stop_reason(session),
+                }),
+            )
+            .await?;
+            *transaction = None;
+            self.transaction_id = None;
+        }
+
+        let status = status(state);
+        if self.status != Some(status) {
+            self.call(
+                "StatusNotification",
+                json!({
+                    "connectorId": CONNECTOR_ID,
+                    "status": status.0,
+                    "errorCode": status.1,
+                    "vendorErrorCode": state.error_code.map(|code| code.to_string()),
+                    "timestamp": now,
+                }),
+            )
+            .await?;
+            self.status = Some(status);
+        }
+
+        if start && let Some(session) = session {
+            let meter_start = meter - (session.kilo_watt_hours * 1000.0).round() as i64;
+            let res = self
+                .call(
+                    "StartTransaction",
+                    json!({
+                        "connectorId": CONNECTOR_ID,
+                        "idTag": id_tag(session, self.remote_start.as_ref()),
+                        "meterStart": meter_start,
+                        "timestamp": session.car_connected.format_zulu(),
+                    }),
+                )
+                .await?;
+
+            let transaction_id = res["transactionId"]
+                .as_i64()
+                .ok_or_else(|| anyhow!("StartTransaction without transactionId: {res}"))?;
+
+            *transaction = Some(Transaction {
+                session_id: session.id,
+                transaction_id,
+            });
+            self.transaction_id = Some(transaction_id);
+            self.remote_start = None;
+            self.last_meter_values = None;
+        }
+
+        let meter_due = self
+            .last_meter_values
+            .is_none_or(|sent| sent.elapsed() >= self.settings.meter_value_sample_interval);
+
+        if let Some(tx) = transaction
+            && meter_due
+        {
+            let mut sampled = vec![json!({
+                "value": meter.to_string(),
+                "measurand": "Energy.Active.Import.Register",
+                "unit": "Wh",
+            })];
+            if let Some(kw) = state.total_power {
+                sampled.push(json!({
+                    "value": ((kw * 1000.0).round() as i64).to_string(),
+                    "measurand": "Power.Active.Import",
+                    "unit": "W",
+                }));
+            }
+
+            let meter_values = json!({
+                "connectorId": CONNECTOR_ID,
+                "transactionId": tx.transaction_id,
+                "meterValue": [{ "timestamp": now, "sampledValue": sampled }],
+            });
+            self.call("MeterValues", meter_values).await?;
+            self.last_meter_values = Some(Instant::now());
+        }
+
+        Ok(())
+    }
+
+    /// Sends a call and waits for its result, answering calls from the CSMS meanwhile
+    async fn call(&mut self, action: &str, payload: Value) -> Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id.to_string();
+
+        debug!(charger_id = self.charger_id, action, "call");
+        self.send(Frame::Call {
+            id: id.clone(),
+            action: action.to_string(),
+            payload,
+        })
+        .await?;
+
+        let deadline = Instant::now() + CALL_TIMEOUT;
+        loop {
+            let frame = tokio::time::timeout_at(deadline, self.ws.next())
+                .await
+                .map_err(|_| anyhow!("{action} timed out"))?;
+
+            let text = match frame {
+                Some(Ok(tungstenite::Message::Text(text))) => text,
+                Some(Ok(tungstenite::Message::Close(_))) | None => bail!("closed by csms"),
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => return Err(err.into()),
+            };
+
+            match Frame::decode(&text) {
+                Ok(Frame::CallResult {
+                    id: res_id,
+                    payload,
+                }) if res_id == id => return Ok(payload),
+                Ok(Frame::CallError {
+                    id: res_id,
+                    code,
+                    description,
+                }) if res_id == id => bail!("{action} failed: {code} {description}"),
+                // Calls from the CSMS, and invalid frames to be logged
+                _ => self.handle_frame(&text).await?,
+            }
+        }
+    }
+
+    async fn handle_frame(&mut self, text: &str) -> Result<()> {
+        let (id, action, payload) = match Frame::decode(text) {
+            Ok(Frame::Call {
+                id,
+                action,
+                payload,
+            }) => (id, action, payload),
+            Ok(frame) => {
+                debug!(
+                    charger_id = self.charger_id,
+                    ?frame,
+                    "ignoring unexpected frame"
+                );
+                return Ok(());
+            }
+            Err(err) => {
+                warn!(charger_id = self.charger_id, "invalid frame: {err}");
+                return Ok(());
+            }
+        };
+
+        info!(charger_id = self.charger_id, action, "call from csms");
+
+        let reply = match handle_call(&action, &payload, &mut self.settings, self.transaction_id) {
+            Reply::Respond(payload) => Frame::CallResult { id, payload },
+
+            Reply::Command(command) => {
+                let accepted = match SendChargerCommand::new(&self.charger_id, command)
+                    .send(&self.client)
+                    .await
+                {
+                    Ok(_) => true,
+                    Err(err) => {
+                        warn!(
+                            charger_id = self.charger_id,
+                            action, "command failed: {err}"
+                        );
+                        false
+                    }
+                };
+
+                if accepted && action == "RemoteStartTransaction" {
+                    let id_tag = payload["idTag"].as_str().unwrap_or_default();
+                    self.remote_start = Some((id_tag.to_string(), Instant::now()));
+                }
+
+                let status = if accepted { "Accepted" } else { "Rejected" };
+                Frame::CallResult {
+                    id,
+                    payload: json!({ "status": status }),
+                }
+            }
+
+            Reply::Error { code, description } => Frame::CallError {
+                id,
+                code: code.to_string(),
+                description,
+            },
+        };
+
+        self.send(reply).await
+    }
+
+    async fn send(&mut self, frame: Frame) -> Result<()> {
+        self.ws
+            .send(tungstenite::Message::Text(frame.encode().into()))
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(json: &str) -> ChargerState {
+        serde_json::from_str(json).expect("state")
+    }
+
+    fn session(id: i64) -> ChargerSession {
+        serde_json::from_value(json!({
+            "id": id,
+            "carConnected": "2023-08-20T12:00:00Z",
+            "kiloWattHours": 1.5,
+            "pricePerKwhExcludingVat": 0.0,
+            "pricePrKwhIncludingVat": 0.0,
+            "costExcludingVat": 0.0,
+            "costIncludingVat": 0.0,
+        }))
+        .expect("session")
+    }
+
+    #[test]
+    fn status_from_state() {
+        assert_eq!(
+            status(&state(r#"{"chargerOpMode":1}"#)),
+            ("Available", "NoError")
+        );
+        assert_eq!(
+            status(&state(r#"{"chargerOpMode":3}"#)),
+            ("Charging", "NoError")
+        );
+        assert_eq!(
+            status(&state(r#"{"chargerOpMode":5}"#)),
+            ("Faulted", "OtherError")
+        );
+        assert_eq!(
+            status(&state(r#"{"chargerOpMode":3,"isOnline":false}"#)),
+            ("Unavailable", "NoError")
+        );
+
+        assert_eq!(
+            meter_wh(&state(r#"{"chargerOpMode":1,"lifetimeEnergy":1234.5678}"#)),
+            1234568
+        );
+    }
+
+    #[test]
+    fn transactions_follow_sessions() {
+        let tx = Transaction {
+            session_id: 4,
+            transaction_id: 100,
+        };
+
+        assert_eq!(transaction_changes(None, None), (false, false));
+        assert_eq!(transaction_changes(None, Some(&session(4))), (false, true));
+        assert_eq!(
+            transaction_changes(Some(&tx), Some(&session(4))),
+            (false, false)
+        );
+        assert_eq!(
+            transaction_changes(Some(&tx), Some(&session(5))),
+            (true, true)
+        );
+        assert_eq!(transaction_changes(Some(&tx), None), (true, false));
+
+        assert_eq!(stop_reason(None), "EVDisconnected");
+        assert_eq!(stop_reason(Some(&session(5))), "Other");
+    }
+
+    #[test]
+    fn id_tags() {
+        let remote = ("REMOTE".to_string(), Instant::now());
+        let mut authorized = session(4);
+        authorized.auth_token = Some("RFID".into());
+
+        assert_eq!(id_tag(&session(4), None), DEFAULT_ID_TAG);
+        assert_eq!(id_tag(&session(4), Some(&remote)), "REMOTE");
+        assert_eq!(id_tag(&authorized, Some(&remote)), "RFID");
+
+        let expired = ("REMOTE".to_string(), Instant::now() - REMOTE_START_TIMEOUT);
+        assert_eq!(id_tag(&session(4), Some(&expired)), DEFAULT_ID_TAG);
+    }
+
+    #[test]
+    fn reconnect_during_session() {
+        // Kept by `keep_connected` across connections
+        let mut transaction = None;
+
+        // First connection sees the session and starts a transaction
+        assert_eq!(
+            transaction_changes(transaction.as_ref(), Some(&session(4))),
+            (false, true)
+        );
+        transaction = Some(Transaction {
+            session_id: 4,
+            transaction_id: 100,
+        });
+
+        // The next connection gets the same transaction, and doesn't start another
+        assert_eq!(
+            transaction_changes(transaction.as_ref(), Some(&session(4))),
+            (false, false)
+        );
+        // until the session ends
+        assert_eq!(
+            transaction_changes(transaction.as_ref(), None),
+            (true, false)
+        );
+    }
+
+    #[test]
+    fn remote_transactions() {
+        let mut settings = Settings::default();
+        let rejected = Reply::Respond(json!({"status": "Rejected"}));
+        let mut call = |action, payload, transaction_id| {
+            handle_call(action, &payload, &mut settings, transaction_id)
+        };
+
+        // Only the ongoing transaction can be stopped
+        assert_eq!(
+            call(
+                "RemoteStopTransaction",
+                json!({"transactionId": 7}),
+                Some(8)
+            ),
+            rejected
+        );
+        assert_eq!(
+            call("RemoteStopTransaction", json!({"transactionId": 7}), None),
+            rejected
+        );
+
+        assert_eq!(
+            call("RemoteStartTransaction", json!({"idTag": "ABC"}), None),
+            Reply::Command(ChargerCommand::StartCharging)
+        );
+        assert_eq!(
+            call("RemoteStartTransaction", json!({"idTag": "ABC"}), Some(8)),
+            rejected
+        );
+        assert_eq!(
+            call(
+                "RemoteStartTransaction",
+                json!({"idTag": "ABC", "connectorId": 2}),
+                None
+            ),
+            rejected
+        );
+        assert_eq!(
+            call("RemoteStartTransaction", json!({"connectorId": 1}), None),
+            rejected
+        );
+
+        // Resetting would stop the transaction without telling the csms
+        assert_eq!(call("Reset", json!({"type": "Soft"}), Some(8)), rejected);
+    }
+
+    #[test]
+    fn calls_from_csms() {
+        let mut settings = Settings::default();
+
+        assert_eq!(
+            handle_call(
+                "RemoteStopTransaction",
+                &json!({"transactionId": 1}),
+                &mut settings,
+                Some(1)
+            ),
+            Reply::Command(ChargerCommand::StopCharging)
+        );
+        assert_eq!(
+            handle_call("Reset", &json!({"type": "Hard"}), &mut settings, None),
+            Reply::Command(ChargerCommand::Reboot)
+        );
+
+        let change = json!({"key": "HeartbeatInterval", "value": "60"});
+        assert_eq!(
+            handle_call("ChangeConfiguration", &change, &mut settings, None),
+            Reply::Respond(json!({"status": "Accepted"}))
+        );
+        assert_eq!(settings.heartbeat_interval, Duration::from_secs(60));
+
+        let unknown = json!({"key": "LocalAuthListEnabled", "value": "true"});
+        assert_eq!(
+            handle_call("ChangeConfiguration", &unknown, &mut settings, None),
+            Reply::Respond(json!({"status": "NotSupported"}))
+        );
+
+        assert!(matches!(
+            handle_call("UpdateFirmware", &json!({}), &mut settings, None),
+            Reply::Error {
+                code: "NotImplemented",
+                ..
+            }
+        ));
+    }
+}
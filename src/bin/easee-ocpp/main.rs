@@ -0,0 +1,121 @@
+//! Presents every Easee charger visible to the account as an OCPP 1.6J charge point.
+//!
+//! Each charger gets its own WebSocket connection to `{CSMS_URL}/{charger_id}`. Status
+//! notifications, transactions and meter values are derived from polling the charger state and
+//! ongoing session, and remote start/stop, reset and configuration changes from the CSMS are
+//! translated into charger commands.
+//!
+//! Configured through the environment:
+//! * `EASEE_ACCESS_TOKEN`, `EASEE_REFRESH_TOKEN` - see `Client::from_env`
+//! * `CSMS_URL`, eg `ws://localhost:9000/ocpp`
+//! * `POLL_INTERVAL` in seconds (30)
+//! * `CHARGERS`, comma separated charger ids, defaults to all chargers on all sites
+//!
+//! Any WebSocket server can stand in for a CSMS while testing, eg:
+//!
+//! ```sh
+//! websocat -s 9000 --protocol ocpp1.6 &
+//! CSMS_URL=ws://localhost:9000 cargo run --features ocpp --bin easee-ocpp
+//! ```
+
+mod charge_point;
+mod messages;
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use easeeapi::{
+    Client,
+    requests::{GetSite, GetSites},
+};
+use tracing::{info, warn};
+
+use charge_point::{ChargePoint, Transaction};
+
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().init();
+
+    let csms_url = std::env::var("CSMS_URL").context("CSMS_URL not set")?;
+    let poll_interval = Duration::from_secs(
+        std::env::var("POLL_INTERVAL")
+            .unwrap_or_else(|_| "30".into())
+            .parse()
+            .context("POLL_INTERVAL")?,
+    );
+
+    let client = Client::from_env()?;
+
+    let charger_ids = match std::env::var("CHARGERS") {
+        Ok(ids) => ids.split(',').map(|id| id.trim().to_string()).collect(),
+        Err(_) => all_chargers(&client).await?,
+    };
+    info!(chargers = charger_ids.len(), "bridging to {csms_url}");
+
+    for charger_id in charger_ids {
+        tokio::spawn(keep_connected(
+            client.clone(),
+            charger_id,
+            csms_url.clone(),
+            poll_interval,
+        ));
+    }
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
+
+async fn all_chargers(client: &Client) -> Result<Vec<String>> {
+    let mut charger_ids = Vec::new();
+
+    let mut pages = GetSites::default().pages();
+    while let Some(site) = pages.next(client).await? {
+        let site = match GetSite(site.id).send(client).await {
+            Ok(site) => site,
+            Err(err) => {
+                warn!(site_id = %site.id, "fetching site failed, skipping: {err}");
+                continue;
+            }
+        };
+
+        charger_ids.extend(
+            site.circuits
+                .iter()
+                .flat_map(|c| &c.chargers)
+                .map(|c| c.id.clone()),
+        );
+    }
+
+    Ok(charger_ids)
+}
+
+async fn keep_connected(
+    client: Client,
+    charger_id: String,
+    csms_url: String,
+    poll_interval: Duration,
+) {
+    let mut backoff = Duration::from_secs(1);
+    // Outlives the connections, so a session is one transaction across reconnects
+    let mut transaction: Option<Transaction> = None;
+
+    loop {
+        let result = match ChargePoint::connect(client.clone(), charger_id.clone(), &csms_url).await
+        {
+            Ok(charge_point) => {
+                backoff = Duration::from_secs(1);
+                charge_point.run(poll_interval, &mut transaction).await
+            }
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            warn!(charger_id, "charge point disconnected: {err:#}");
+        }
+
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,122 @@
+//! OCPP-J framing, `[2, id, action, payload]` calls answered by `[3, id, payload]` results or
+//! `[4, id, code, description, details]` errors.
+
+use serde_json::{Value, json};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Call {
+        id: String,
+        action: String,
+        payload: Value,
+    },
+    CallResult {
+        id: String,
+        payload: Value,
+    },
+    CallError {
+        id: String,
+        code: String,
+        description: String,
+    },
+}
+
+impl Frame {
+    pub fn decode(text: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<Value>(text).map_err(|err| err.to_string())?;
+        let Some(parts) = value.as_array() else {
+            return Err(format!("not an array: {text}"));
+        };
+
+        let str_at = |n: usize| {
+            parts
+                .get(n)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| format!("missing string at {n}: {text}"))
+        };
+
+        match parts.first().and_then(Value::as_u64) {
+            Some(2) => Ok(Frame::Call {
+                id: str_at(1)?,
+                action: str_at(2)?,
+                payload: parts.get(3).cloned().unwrap_or(Value::Null),
+            }),
+            Some(3) => Ok(Frame::CallResult {
+                id: str_at(1)?,
+                payload: parts.get(2).cloned().unwrap_or(Value::Null),
+            }),
+            Some(4) => Ok(Frame::CallError {
+                id: str_at(1)?,
+                code: str_at(2)?,
+                description: str_at(3).unwrap_or_default(),
+            }),
+            _ => Err(format!("unknown message type: {text}")),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let value = match self {
+            Frame::Call {
+                id,
+                action,
+                payload,
+            } => json!([2, id, action, payload]),
+            Frame::CallResult { id, payload } => json!([3, id, payload]),
+            Frame::CallError {
+                id,
+                code,
+                description,
+            } => json!([4, id, code, description, {}]),
+        };
+
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let frames = [
+            Frame::Call {
+                id: "1".into(),
+                action: "Heartbeat".into(),
+                payload: json!({}),
+            },
+            Frame::CallResult {
+                id: "1".into(),
+                payload: json!({"currentTime": "2023-08-20T12:00:00Z"}),
+            },
+            Frame::CallError {
+                id: "2".into(),
+                code: "NotImplemented".into(),
+                description: "UpdateFirmware".into(),
+            },
+        ];
+
+        for frame in frames {
+            assert_eq!(Frame::decode(&frame.encode()), Ok(frame));
+        }
+    }
+
+    #[test]
+    fn decode_csms_call() {
+        let frame = Frame::decode(r#"[2,"abc","RemoteStopTransaction",{"transactionId":7}]"#)
+            .expect("decode");
+
+        assert_eq!(
+            frame,
+            Frame::Call {
+                id: "abc".into(),
+                action: "RemoteStopTransaction".into(),
+                payload: json!({"transactionId": 7}),
+            }
+        );
+
+        assert!(Frame::decode(r#"[9,"abc"]"#).is_err());
+        assert!(Frame::decode(r#"{"not":"ocpp"}"#).is_err());
+    }
+}
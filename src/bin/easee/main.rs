@@ -0,0 +1,453 @@
+//! `easee`, everyday operations from the command line.
+//!
+//! Log in once with `easee login -u <email>`, which saves the tokens to
+//! `~/.config/easee/credentials.json` (or `$EASEE_CREDENTIALS`). Refreshed tokens are saved
+//! after every command. Without saved credentials `EASEE_ACCESS_TOKEN` and
+//! `EASEE_REFRESH_TOKEN` are used.
+
+mod output;
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use clap::{Parser, Subcommand};
+use easeeapi::{
+    Client, OptionalResult,
+    requests::{
+        ChargerCommand, GetChargerSessions, GetChargerState, GetOngoingSession, GetSite, GetSites,
+        SendChargerCommand,
+    },
+};
+
+use output::{Format, Listing, cell};
+
+#[derive(Parser)]
+#[command(name = "easee", about = "Manage Easee chargers and sites")]
+struct Cli {
+    /// Output format
+    #[arg(long, short, global = true, value_enum, default_value = "table")]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Log in and save the credentials for later commands
+    Login {
+        #[arg(long, short)]
+        username: String,
+        #[arg(long, short, env = "EASEE_PASSWORD", hide_env_values = true)]
+        password: String,
+    },
+
+    /// List sites
+    Sites {
+        #[arg(long)]
+        search: Option<String>,
+    },
+
+    /// List chargers on all sites, or one
+    Chargers {
+        #[arg(long)]
+        site: Option<i64>,
+    },
+
+    /// Show the current state of a charger
+    State { charger_id: String },
+
+    /// Start charging
+    Start { charger_id: String },
+
+    /// Stop charging
+    Stop { charger_id: String },
+
+    /// Pause charging, keeping the session
+    Pause { charger_id: String },
+
+    /// Resume paused charging
+    Resume { charger_id: String },
+
+    /// Export sessions which ended between two dates, both inclusive
+    Sessions {
+        charger_id: String,
+        /// YYYY-MM-DD
+        #[arg(long, value_parser = parse_date)]
+        from: time::Date,
+        /// YYYY-MM-DD, defaults to today
+        #[arg(long, value_parser = parse_date)]
+        to: Option<time::Date>,
+    },
+
+    /// Show the ongoing session of a charger
+    Session { charger_id: String },
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedCredentials {
+    access_token: String,
+    refresh_token: String,
+}
+
+fn credentials_path() -> Result<PathBuf> {
+    if let Ok(path) = std::env::var("EASEE_CREDENTIALS") {
+        return Ok(path.into());
+    }
+
+    let config = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => PathBuf::from(std::env::var("HOME").context("HOME not set")?).join(".config"),
+    };
+
+    Ok(config.join("easee").join("credentials.json"))
+}
+
+async fn save_credentials(client: &Client) -> Result<()> {
+    let credentials = client.get_credentials().await;
+    let saved = SavedCredentials {
+        access_token: credentials.session.raw,
+        refresh_token: credentials.refresh_token,
+    };
+
+    write_credentials(&credentials_path()?, &saved)
+}
+
+fn write_credentials(path: &std::path::Path, saved: &SavedCredentials) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new();
+    file.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut file, 0o600);
+
+    let file = file
+        .open(path)
+        .with_context(|| format!("saving credentials to {}", path.display()))?;
+    // `mode` only applies to new files, an existing one may be readable by others
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))
+        .with_context(|| format!("restricting permissions of {}", path.display()))?;
+    serde_json::to_writer(file, saved)?;
+
+    Ok(())
+}
+
+fn load_client() -> Result<Client> {
+    let path = credentials_path()?;
+
+    match std::fs::read(&path) {
+        Ok(json) => {
+            let saved = serde_json::from_slice::<SavedCredentials>(&json)
+                .with_context(|| format!("reading {}", path.display()))?;
+            Ok(Client::new(saved.access_token, saved.refresh_token)?)
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            Client::from_env().context("not logged in, run `easee login` or set EASEE_ACCESS_TOKEN")
+        }
+        Err(err) => Err(err).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+fn parse_date(s: &str) -> Result<time::Date, String> {
+    let format = time::macros::format_description!("[year]-[month]-[day]");
+    time::Date::parse(s, format).map_err(|err| err.to_string())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+
+    if let Command::Login { username, password } = &cli.command {
+        let client = Client::login(username, password).await?;
+        save_credentials(&client).await?;
+
+        eprintln!(
+            "logged in, credentials saved to {}",
+            credentials_path()?.display()
+        );
+        return Ok(());
+    }
+
+    let client = load_client()?;
+    let issued_at = client.get_credentials_issued_at().await;
+
+    let result = run(&client, cli.command, cli.format).await;
+
+    // Keep refreshed tokens, the old refresh token is no longer valid
+    if client.get_credentials_issued_at().await != issued_at {
+        save_credentials(&client).await?;
+    }
+
+    result
+}
+
+async fn run(client: &Client, command: Command, format: Format) -> Result<()> {
+    match command {
+        Command::Login { .. } => unreachable!("handled before loading credentials"),
+
+        Command::Sites { search } => {
+            let mut req = GetSites::default();
+            if let Some(search) = search {
+                req = req.search(search);
+            }
+
+            let mut sites = Vec::new();
+            let mut pages = req.pages();
+            while let Some(site) = pages.next(client).await? {
+                sites.push(site);
+            }
+
+            Listing {
+                headers: &["id", "key", "name", "city"],
+                rows: sites
+                    .iter()
+                    .map(|site| {
+                        vec![
+                            site.id.to_string(),
+                            site.site_key.clone(),
+                            site.name.clone(),
+                            cell(site.address.area.as_ref()),
+                        ]
+                    })
+                    .collect(),
+                data: &sites,
+            }
+            .print(format)
+        }
+
+        Command::Chargers { site } => {
+            let mut sites = Vec::new();
+            match site {
+                Some(id) => sites.push(GetSite(easeeapi::SiteId(id)).send(client).await?),
+                None => {
+                    let mut pages = GetSites::default().pages();
+                    while let Some(site) = pages.next(client).await? {
+                        match GetSite(site.id).send(client).await {
+                            Ok(site) => sites.push(site),
+                            Err(err) => eprintln!("skipping site {}: {err}", site.id),
+                        }
+                    }
+                }
+            }
+
+            let chargers = sites
+                .iter()
+                .flat_map(|site| {
+                    site.circuits.iter().flat_map(move |circuit| {
+                        circuit
+                            .chargers
+                            .iter()
+                            .map(move |charger| (site, circuit, charger))
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let data = chargers
+                .iter()
+                .map(|(_, _, charger)| charger)
+                .collect::<Vec<_>>();
+            Listing {
+                headers: &["id", "name", "site_id", "site", "circuit"],
+                rows: chargers
+                    .iter()
+                    .map(|(site, circuit, charger)| {
+                        vec![
+                            charger.id.clone(),
+                            charger.name.clone(),
+                            site.id.to_string(),
+                            site.name.clone(),
+                            circuit.panel_name.clone(),
+                        ]
+                    })
+                    .collect(),
+                data: &data,
+            }
+            .print(format)
+        }
+
+        Command::State { charger_id } => {
+            let state = GetChargerState::new(&charger_id).send(client).await?;
+
+            Listing {
+                headers: &[
+                    "charger",
+                    "mode",
+                    "online",
+                    "power_kw",
+                    "session_kwh",
+                    "lifetime_kwh",
+                ],
+                rows: vec![vec![
+                    charger_id,
+                    format!("{:?}", state.charger_op_mode),
+                    cell(state.is_online),
+                    cell(state.total_power),
+                    cell(state.session_energy),
+                    cell(state.lifetime_energy),
+                ]],
+                data: &state,
+            }
+            .print(format)
+        }
+
+        Command::Start { charger_id } => {
+            send_command(client, &charger_id, ChargerCommand::StartCharging).await
+        }
+        Command::Stop { charger_id } => {
+            send_command(client, &charger_id, ChargerCommand::StopCharging).await
+        }
+        Command::Pause { charger_id } => {
+            send_command(client, &charger_id, ChargerCommand::PauseCharging).await
+        }
+        Command::Resume { charger_id } => {
+            send_command(client, &charger_id, ChargerCommand::ResumeCharging).await
+        }
+
+        Command::Sessions {
+            charger_id,
+            from,
+            to,
+        } => {
+            let to = to.unwrap_or_else(|| easeeapi::DateTime::now_utc().0.date());
+            if to < from {
+                bail!("--to {to} is before --from {from}");
+            }
+
+            // The api excludes `to`
+            let sessions = GetChargerSessions::new(&charger_id, from, to.next_day().unwrap_or(to))
+                .send(client)
+                .await?;
+
+            Listing {
+                headers: &[
+                    "id",
+                    "car_connected",
+                    "car_disconnected",
+                    "kwh",
+                    "cost",
+                    "currency",
+                ],
+                rows: sessions
+                    .iter()
+                    .map(|s| {
+                        vec![
+                            s.id.to_string(),
+                            s.car_connected.to_string(),
+                            cell(s.car_disconnected),
+                            s.kilo_watt_hours.to_string(),
+                            s.cost_including_vat.to_string(),
+                            cell(s.currency.as_ref()),
+                        ]
+                    })
+                    .collect(),
+                data: &sessions,
+            }
+            .print(format)
+        }
+
+        Command::Session { charger_id } => {
+            let Some(session) = GetOngoingSession::new(&charger_id)
+                .send(client)
+                .await
+                .optional()?
+            else {
+                eprintln!("no ongoing session on {charger_id}");
+                return Ok(());
+            };
+
+            Listing {
+                headers: &["id", "car_connected", "kwh"],
+                rows: vec![vec![
+                    session.id.to_string(),
+                    session.car_connected.to_string(),
+                    session.kilo_watt_hours.to_string(),
+                ]],
+                data: &session,
+            }
+            .print(format)
+        }
+    }
+}
+
+async fn send_command(client: &Client, charger_id: &str, command: ChargerCommand) -> Result<()> {
+    let res = SendChargerCommand::new(charger_id, command)
+        .send(client)
+        .await?;
+    eprintln!(
+        "sent {} to {charger_id} ({})",
+        command.name(),
+        res.command_id
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[cfg(unix)]
+    #[test]
+    fn credentials_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path =
+            std::env::temp_dir().join(format!("easee-credentials-{}.json", std::process::id()));
+        std::fs::write(&path, "{}").expect("existing file");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).expect("chmod");
+
+        let saved = SavedCredentials {
+            access_token: "access".into(),
+            refresh_token: "refresh".into(),
+        };
+        write_credentials(&path, &saved).expect("write");
+
+        let mode = std::fs::metadata(&path)
+            .expect("metadata")
+            .permissions()
+            .mode();
+        let json = std::fs::read_to_string(&path).expect("read");
+        std::fs::remove_file(&path).expect("cleanup");
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(json, r#"{"accessToken":"access","refreshToken":"refresh"}"#);
+    }
+
+    #[test]
+    fn cli_definition() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "easee",
+            "sessions",
+            "EC3VJ7GU",
+            "--from",
+            "2024-04-01",
+            "--to",
+            "2024-04-30",
+            "-f",
+            "csv",
+        ])
+        .expect("parse");
+
+        assert_eq!(cli.format, Format::Csv);
+        assert!(matches!(
+            cli.command,
+            Command::Sessions { from, to: Some(to), .. }
+                if from == time::macros::date!(2024 - 04 - 01) && to == time::macros::date!(2024 - 04 - 30)
+        ));
+
+        assert!(
+            Cli::try_parse_from(["easee", "sessions", "EC3VJ7GU", "--from", "01-04-2024"]).is_err()
+        );
+    }
+}
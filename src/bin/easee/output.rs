@@ -0,0 +1,106 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+/// Rows for table and csv output, next to the full data for json output
+pub struct Listing<'a, T> {
+    pub headers: &'a [&'a str],
+    pub rows: Vec<Vec<String>>,
+    pub data: &'a T,
+}
+
+impl<T: serde::Serialize> Listing<'_, T> {
+    pub fn print(&self, format: Format) -> Result<()> {
+        let stdout = std::io::stdout();
+        self.write(format, &mut stdout.lock())
+    }
+
+    fn write(&self, format: Format, out: &mut impl Write) -> Result<()> {
+        match format {
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut *out, self.data)?;
+                writeln!(out)?;
+            }
+
+            Format::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+                writer.write_record(self.headers)?;
+                for row in &self.rows {
+                    writer.write_record(row)?;
+                }
+                writer.flush()?;
+            }
+
+            Format::Table => {
+                let mut widths = self
+                    .headers
+                    .iter()
+                    .map(|h| h.chars().count())
+                    .collect::<Vec<_>>();
+                for row in &self.rows {
+                    for (width, cell) in widths.iter_mut().zip(row) {
+                        *width = (*width).max(cell.chars().count());
+                    }
+                }
+
+                let line = |cells: &mut dyn Iterator<Item = &str>| {
+                    let padded = cells
+                        .zip(&widths)
+                        .map(|(cell, width)| format!("{cell:<width$}"))
+                        .collect::<Vec<_>>();
+                    padded.join("  ").trim_end().to_string()
+                };
+
+                writeln!(out, "{}", line(&mut self.headers.iter().copied()))?;
+                for row in &self.rows {
+                    writeln!(out, "{}", line(&mut row.iter().map(String::as_str)))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats optional values as empty cells
+pub fn cell<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(format: Format) -> String {
+        let data = vec![("EC3VJ7GU", 7.5)];
+        let listing = Listing {
+            headers: &["charger", "kwh"],
+            rows: vec![
+                vec!["EC3VJ7GU".into(), "7.5".into()],
+                vec!["EC1".into(), "".into()],
+            ],
+            data: &data,
+        };
+
+        let mut out = Vec::new();
+        listing.write(format, &mut out).expect("write");
+        String::from_utf8(out).expect("utf8")
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(render(Format::Table), "charger   kwh\nEC3VJ7GU  7.5\nEC1\n");
+        assert_eq!(render(Format::Csv), "charger,kwh\nEC3VJ7GU,7.5\nEC1,\n");
+        assert_eq!(
+            render(Format::Json),
+            "[\n  [\n    \"EC3VJ7GU\",\n    7.5\n  ]\n]\n"
+        );
+    }
+}
@@ -4,17 +4,17 @@ mod credentials;
 
 pub(crate) use body::*;
 
+use bytes::Bytes;
 use credentials::GetJwt;
 use http::Method;
 
-use std::sync::Arc;
+use leaky_bucket_lite::LeakyBucket;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 
 use crate::{Error, Result, models};
 pub use {auth::Session, credentials::Credentials};
 
-//use leaky_bucket_lite::LeakyBucket;
-
 static BASE_URL: &str = "https://api.easee.com";
 
 #[derive(Debug, thiserror::Error)]
@@ -27,10 +27,20 @@ pub enum FromEnvError {
     InvalidAccessToken(#[from] auth::ParseError),
 }
 
+/// A reply from `Client::raw_request`
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    pub status: http::StatusCode,
+    pub content_type: Option<String>,
+    pub body: Bytes,
+}
+
 #[derive(Clone)]
 pub struct Client {
     c: reqwest::Client,
     credentials: Arc<Mutex<Credentials>>,
+    limiter: Option<LeakyBucket>,
+    base_url: String,
 }
 
 fn build_http_client() -> reqwest::Client {
@@ -55,6 +65,8 @@ impl Client {
         Ok(Client {
             c: build_http_client(),
             credentials: Arc::new(Mutex::new(credentials)),
+            limiter: None,
+            base_url: BASE_URL.into(),
         })
     }
 
@@ -86,14 +98,45 @@ impl Client {
         Ok(Self {
             c,
             credentials: Arc::new(Mutex::new(credentials)),
+            limiter: None,
+            base_url: BASE_URL.into(),
         })
     }
 
-    async fn get_token(&self) -> Result<String> {
+    /// Sends requests to a test server instead of the api
+    #[cfg(test)]
+    pub(crate) fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Limits requests to `requests` per `per`, shared by all clones of this client.
+    ///
+    /// Requests over the limit wait for their turn instead of failing.
+    pub fn with_rate_limit(mut self, requests: u32, per: Duration) -> Self {
+        let requests = requests.max(1);
+
+        self.limiter = Some(
+            LeakyBucket::builder()
+                .max(requests)
+                .tokens(requests)
+                .refill_interval(per / requests)
+                .refill_amount(1)
+                .build(),
+        );
+        self
+    }
+
+    pub(crate) async fn get_token(&self) -> Result<String> {
+        Ok(self.get_session().await?.raw)
+    }
+
+    /// The current session, refreshed first if it's about to expire
+    pub(crate) async fn get_session(&self) -> Result<Session> {
         let mut credentials = self.credentials.lock().await;
 
         let (token, refresh_token) = match credentials.get_valid_session().await {
-            GetJwt::Valid { token } => return Ok(token.into()),
+            GetJwt::Valid => return Ok(credentials.session.clone()),
             GetJwt::Expired {
                 token,
                 refresh_token,
@@ -115,7 +158,7 @@ impl Client {
         credentials.session = res.access_token.parse::<Session>()?;
         credentials.refresh_token = res.refresh_token;
 
-        Ok(res.access_token)
+        Ok(credentials.session.clone())
     }
 
     /// Forces refresh of the current session
@@ -153,6 +196,30 @@ impl Client {
         lock.session.issued_at
     }
 
+    #[cfg(feature = "streaming")]
+    pub(crate) fn http_client(&self) -> &reqwest::Client {
+        &self.c
+    }
+
+    /// Builds a request with the access token, waiting for the rate limit if there is one
+    async fn authenticated(
+        &self,
+        method: http::Method,
+        path: &str,
+        access_token: &str,
+    ) -> reqwest::RequestBuilder {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire_one().await;
+        }
+
+        self.c
+            .request(
+                method,
+                format!("{}/{}", self.base_url, path.trim_start_matches('/')),
+            )
+            .header("authorization", format!("Bearer {access_token}"))
+    }
+
     async fn inner_req<Req, Rep>(
         &self,
         method: http::Method,
@@ -164,17 +231,45 @@ impl Client {
         Req: RequestBody,
         Rep: ResponseBody,
     {
-        let b = self
-            .c
-            .request(
-                method,
-                format!("{}/{}", BASE_URL, path.trim_start_matches('/')),
-            )
-            .header("authorization", format!("Bearer {access_token}"));
+        let b = self.authenticated(method, path, access_token).await;
 
         send_and_handle_response::<Rep>(body.apply_to(b)).await
     }
 
+    /// Sends any request to the api with this client's session and rate limit, returning the
+    /// reply as is, error statuses included. `path` may include a query string.
+    ///
+    /// Meant for proxying, and for endpoints without a request type in this crate.
+    pub async fn raw_request(
+        &self,
+        method: http::Method,
+        path: &str,
+        content_type: Option<&str>,
+        body: Bytes,
+    ) -> Result<RawResponse> {
+        let access_token = self.get_token().await?;
+
+        let mut b = self.authenticated(method, path, &access_token).await;
+        if let Some(content_type) = content_type {
+            b = b.header("content-type", content_type);
+        }
+        if !body.is_empty() {
+            b = b.body(body);
+        }
+
+        let res = b.send().await?;
+
+        Ok(RawResponse {
+            status: res.status(),
+            content_type: res
+                .headers()
+                .get("content-type")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+            body: res.bytes().await?,
+        })
+    }
+
     pub(crate) async fn req<Req, Rep>(
         &self,
         method: http::Method,
@@ -191,6 +286,24 @@ impl Client {
     }
 }
 
+/// Sends a request that does not require a session, like registering or resetting passwords
+pub(crate) async fn unauthenticated_req<Req, Rep>(
+    method: http::Method,
+    path: &str,
+    body: Req,
+) -> Result<Rep::Data>
+where
+    Req: RequestBody,
+    Rep: ResponseBody,
+{
+    let b = build_http_client().request(
+        method,
+        format!("{}/{}", BASE_URL, path.trim_start_matches('/')),
+    );
+
+    send_and_handle_response::<Rep>(body.apply_to(b)).await
+}
+
 async fn send_and_handle_response<Rep>(builder: reqwest::RequestBuilder) -> Result<Rep::Data>
 where
     Rep: ResponseBody,
@@ -11,6 +11,7 @@ pub(crate) trait ResponseBody {
 }
 
 pub(crate) struct JsonBody<T>(pub T);
+pub(crate) struct QueryParams<T>(pub T);
 pub(crate) struct NoBody;
 pub(crate) struct BytesBody;
 
@@ -23,6 +24,15 @@ where
     }
 }
 
+impl<T> RequestBody for QueryParams<T>
+where
+    T: serde::Serialize,
+{
+    fn apply_to(self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        builder.query(&self.0)
+    }
+}
+
 impl RequestBody for NoBody {
     fn apply_to(self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         builder
@@ -9,9 +9,7 @@ pub struct Credentials {
 }
 
 pub enum GetJwt<'a> {
-    Valid {
-        token: &'a str,
-    },
+    Valid,
     Expired {
         token: &'a str,
         refresh_token: &'a str,
@@ -26,9 +24,7 @@ impl Credentials {
         let graced_expired_at = self.session.expires_at.0 - time::Duration::from_secs(60);
 
         if now < graced_expired_at {
-            GetJwt::Valid {
-                token: &self.session.raw,
-            }
+            GetJwt::Valid
         } else {
             GetJwt::Expired {
                 token: &self.session.raw,
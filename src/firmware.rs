@@ -0,0 +1,327 @@
+//! Rolling out firmware updates to many chargers without disturbing ongoing charging.
+
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::{
+    Client, OptionalResult, Result,
+    requests::{
+        ChargerCommand, FirmwareInfo, GetChargerState, GetFirmware, GetOngoingSession,
+        SendChargerCommand,
+    },
+};
+
+/// What happened to a single charger during a rollout
+#[derive(Debug, Clone, PartialEq)]
+pub enum RolloutOutcome {
+    Updated {
+        from: Option<i32>,
+        to: Option<i32>,
+    },
+    UpToDate,
+    SkippedOngoingSession,
+    Failed(String),
+    /// An earlier wave failed, so the charger was left alone
+    NotAttempted,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RolloutReport {
+    pub outcomes: Vec<(String, RolloutOutcome)>,
+    pub aborted: bool,
+}
+
+impl RolloutReport {
+    pub fn failed(&self) -> impl Iterator<Item = &str> {
+        self.outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, RolloutOutcome::Failed(_)))
+            .map(|(id, _)| id.as_str())
+    }
+}
+
+/// Updates chargers in waves using the `update_firmware` command.
+///
+/// Chargers that are up to date or have an ongoing session are skipped. After sending the
+/// command to every charger in a wave, the rollout waits for each of them to come back online
+/// on the new firmware before starting the next wave. If any charger fails, no more update
+/// commands are sent and the remaining chargers are reported as `NotAttempted`.
+pub struct FirmwareRollout {
+    charger_ids: Vec<String>,
+    wave_size: usize,
+    poll_interval: Duration,
+    online_timeout: Duration,
+}
+
+impl FirmwareRollout {
+    pub fn new(charger_ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            charger_ids: charger_ids.into_iter().map(Into::into).collect(),
+            wave_size: 5,
+            poll_interval: Duration::from_secs(30),
+            online_timeout: Duration::from_secs(15 * 60),
+        }
+    }
+
+    pub fn wave_size(mut self, wave_size: usize) -> Self {
+        self.wave_size = wave_size.max(1);
+        self
+    }
+
+    /// How often chargers are polled while waiting for them to come back
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// How long a charger may take to come back online before it's considered failed
+    pub fn online_timeout(mut self, online_timeout: Duration) -> Self {
+        self.online_timeout = online_timeout;
+        self
+    }
+
+    pub async fn run(&self, client: &Client) -> RolloutReport {
+        let mut report = RolloutReport::default();
+
+        for (n, wave) in self.charger_ids.chunks(self.wave_size).enumerate() {
+            if report.aborted {
+                report.outcomes.extend(
+                    wave.iter()
+                        .map(|id| (id.clone(), RolloutOutcome::NotAttempted)),
+                );
+                continue;
+            }
+
+            info!(wave = n, chargers = wave.len(), "starting firmware wave");
+
+            let mut pending = Vec::new();
+            for charger_id in wave {
+                if report.aborted {
+                    report
+                        .outcomes
+                        .push((charger_id.clone(), RolloutOutcome::NotAttempted));
+                    continue;
+                }
+
+                match self.start_update(client, charger_id).await {
+                    Ok(from) => pending.push((charger_id, from)),
+                    Err(outcome) => {
+                        report.aborted = matches!(outcome, RolloutOutcome::Failed(_));
+                        report.outcomes.push((charger_id.clone(), outcome));
+                    }
+                }
+            }
+
+            for (charger_id, from) in pending {
+                let outcome = self.await_online(client, charger_id, from).await;
+                report.outcomes.push((charger_id.clone(), outcome));
+            }
+
+            let wave_failed = report
+                .outcomes
+                .iter()
+                .filter(|(id, _)| wave.contains(id))
+                .any(|(_, outcome)| matches!(outcome, RolloutOutcome::Failed(_)));
+
+            if wave_failed {
+                warn!(wave = n, "firmware wave failed, aborting rollout");
+                report.aborted = true;
+            }
+        }
+
+        report
+    }
+
+    /// Sends the update command if the charger needs and can take it.
+    /// Returns the firmware it was on, or the outcome if it was left alone.
+    async fn start_update(
+        &self,
+        client: &Client,
+        charger_id: &str,
+    ) -> std::result::Result<FirmwareInfo, RolloutOutcome> {
+        let firmware = GetFirmware::charger(charger_id)
+            .send(client)
+            .await
+            .map_err(failed)?;
+
+        if !firmware.update_available() {
+            return Err(RolloutOutcome::UpToDate);
+        }
+
+        let ongoing = GetOngoingSession::new(charger_id)
+            .send(client)
+            .await
+            .optional()
+            .map_err(failed)?;
+
+        if ongoing.is_some() {
+            return Err(RolloutOutcome::SkippedOngoingSession);
+        }
+
+        SendChargerCommand::new(charger_id, ChargerCommand::UpdateFirmware)
+            .send(client)
+            .await
+            .map_err(failed)?;
+
+        info!(charger_id, from = ?firmware.current, to = ?firmware.latest, "firmware update sent");
+
+        Ok(firmware)
+    }
+
+    async fn await_online(
+        &self,
+        client: &Client,
+        charger_id: &str,
+        from: FirmwareInfo,
+    ) -> RolloutOutcome {
+        let started = std::time::Instant::now();
+
+        loop {
+            tokio::time::sleep(self.poll_interval).await;
+
+            match self.is_updated(client, charger_id, &from).await {
+                Ok(Some(to)) => {
+                    return RolloutOutcome::Updated {
+                        from: from.current,
+                        to,
+                    };
+                }
+                Ok(None) => {}
+                Err(err) => warn!(charger_id, "polling charger after update: {err}"),
+            }
+
+            if started.elapsed() >= self.online_timeout {
+                return RolloutOutcome::Failed(format!(
+                    "not back online on new firmware within {:?}",
+                    self.online_timeout
+                ));
+            }
+        }
+    }
+
+    async fn is_updated(
+        &self,
+        client: &Client,
+        charger_id: &str,
+        from: &FirmwareInfo,
+    ) -> Result<Option<Option<i32>>> {
+        let state = GetChargerState::new(charger_id).send(client).await?;
+
+        let online = state.is_online == Some(true);
+        // Keep waiting while the firmware isn't reported, it can't be told apart from a failed
+        // update
+        let updated = match (from.current, state.charger_firmware) {
+            (Some(before), Some(now)) => now > before,
+            _ => false,
+        };
+
+        Ok((online && updated).then_some(state.charger_firmware))
+    }
+}
+
+fn failed(err: crate::Error) -> RolloutOutcome {
+    RolloutOutcome::Failed(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::TestServer;
+
+    const OUTDATED: &str =
+        r#"{"chargerOpMode":1,"isOnline":true,"chargerFirmware":300,"latestFirmware":302}"#;
+    const UPDATED: &str =
+        r#"{"chargerOpMode":1,"isOnline":true,"chargerFirmware":302,"latestFirmware":302}"#;
+    const NO_FIRMWARE: &str = r#"{"chargerOpMode":1,"isOnline":true}"#;
+    const SESSION: &str = r#"{"id":1,"carConnected":"2023-08-20T22:00:00Z","carDisconnected":null,"kiloWattHours":1.5,"pricePerKwhExcludingVat":0.0,"pricePrKwhIncludingVat":0.0,"costExcludingVat":0.0,"costIncludingVat":0.0}"#;
+    const COMMAND: &str = r#"{"device":"EC1","commandId":1,"ticks":1}"#;
+
+    fn rollout(chargers: &[&str], wave_size: usize) -> FirmwareRollout {
+        FirmwareRollout::new(chargers.iter().copied())
+            .wave_size(wave_size)
+            .poll_interval(Duration::ZERO)
+            .online_timeout(Duration::ZERO)
+    }
+
+    #[tokio::test]
+    async fn waves() {
+        let server = TestServer::start(vec![
+            // First wave: EC1 is updated, EC2 is up to date
+            (200, OUTDATED),
+            (404, ""),
+            (200, COMMAND),
+            (200, UPDATED),
+            (200, UPDATED),
+            // Second wave: EC3 is charging
+            (200, OUTDATED),
+            (200, SESSION),
+        ]);
+
+        let report = rollout(&["EC1", "EC2", "EC3"], 2)
+            .run(&server.client())
+            .await;
+
+        assert!(!report.aborted);
+        assert_eq!(
+            report.outcomes,
+            [
+                ("EC2".to_string(), RolloutOutcome::UpToDate),
+                (
+                    "EC1".to_string(),
+                    RolloutOutcome::Updated {
+                        from: Some(300),
+                        to: Some(302)
+                    }
+                ),
+                ("EC3".to_string(), RolloutOutcome::SkippedOngoingSession),
+            ]
+        );
+
+        let calls = server
+            .requests()
+            .into_iter()
+            .map(|r| (r.method, r.path))
+            .collect::<Vec<_>>();
+        let call = |method: &str, path: &str| (method.to_string(), path.to_string());
+        assert_eq!(
+            calls,
+            [
+                call("GET", "/api/chargers/EC1/state"),
+                call("GET", "/api/chargers/EC1/sessions/ongoing"),
+                call("POST", "/api/chargers/EC1/commands/update_firmware"),
+                call("GET", "/api/chargers/EC2/state"),
+                // The second wave only starts once EC1 is back
+                call("GET", "/api/chargers/EC1/state"),
+                call("GET", "/api/chargers/EC3/state"),
+                call("GET", "/api/chargers/EC3/sessions/ongoing"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_firmware_aborts() {
+        let server = TestServer::start(vec![
+            (200, OUTDATED),
+            (404, ""),
+            (200, COMMAND),
+            // Online, but without a firmware version it isn't known to be updated
+            (200, NO_FIRMWARE),
+        ]);
+
+        let report = rollout(&["EC1", "EC2", "EC3"], 1)
+            .run(&server.client())
+            .await;
+
+        assert!(report.aborted);
+        assert_eq!(report.failed().collect::<Vec<_>>(), ["EC1"]);
+        assert_eq!(
+            report.outcomes[1..],
+            [
+                ("EC2".to_string(), RolloutOutcome::NotAttempted),
+                ("EC3".to_string(), RolloutOutcome::NotAttempted),
+            ]
+        );
+        assert_eq!(server.requests().len(), 4);
+    }
+}
@@ -0,0 +1,508 @@
+//! Connectivity and health of every charger the account can see.
+
+use std::{collections::HashMap, mem, time::Duration};
+
+use tracing::{debug, warn};
+
+use crate::{
+    ChargerState, Client, DateTime, ObservationId, Result, SiteId,
+    requests::{GetChargerState, GetObservations, GetSite, GetSites, Observations},
+};
+
+/// When a charger is considered unhealthy
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthThresholds {
+    /// Offline once nothing has been heard from the charger for this long
+    pub offline_after: Duration,
+    /// dBm
+    pub min_wifi_rssi: i32,
+    /// dBm
+    pub min_cell_rssi: i32,
+    /// °C
+    pub max_temperature: f64,
+    /// More reboots than this within `reboot_window` is reported
+    pub max_reboots: usize,
+    pub reboot_window: Duration,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            offline_after: Duration::from_secs(15 * 60),
+            min_wifi_rssi: -80,
+            min_cell_rssi: -105,
+            max_temperature: 75.0,
+            max_reboots: 3,
+            reboot_window: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthIssue {
+    Offline {
+        last_seen: Option<DateTime>,
+    },
+    Error {
+        code: i32,
+    },
+    FatalError {
+        code: i32,
+    },
+    RepeatedReboots {
+        count: usize,
+    },
+    LowSignal {
+        rssi: i32,
+    },
+    HighTemperature {
+        celsius: f64,
+    },
+    /// The charger's state couldn't be fetched
+    CheckFailed {
+        reason: String,
+    },
+}
+
+impl HealthIssue {
+    /// Whether two issues are the same issue. Errors are told apart by their code, other issues
+    /// by their kind, so eg a temperature change isn't raised again.
+    fn same_issue(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Error { code: a }, Self::Error { code: b })
+            | (Self::FatalError { code: a }, Self::FatalError { code: b }) => a == b,
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChargerHealth {
+    pub charger_id: String,
+    pub site_id: SiteId,
+    pub online: bool,
+    pub last_seen: Option<DateTime>,
+    pub issues: Vec<HealthIssue>,
+}
+
+impl ChargerHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A site whose chargers couldn't be checked
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiteCheckFailed {
+    pub site_id: SiteId,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthReport {
+    pub checked_at: DateTime,
+    pub chargers: Vec<ChargerHealth>,
+    pub failed_sites: Vec<SiteCheckFailed>,
+}
+
+impl HealthReport {
+    pub fn unhealthy(&self) -> impl Iterator<Item = &ChargerHealth> {
+        self.chargers.iter().filter(|c| !c.is_healthy())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthChange {
+    Raised(HealthIssue),
+    Cleared(HealthIssue),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthEvent {
+    pub charger_id: String,
+    pub change: HealthChange,
+}
+
+/// Checks every charger on every site returned by `GetSites`.
+///
+/// Each check fetches the charger state, plus reboot and temperature observations over the
+/// last `reboot_window`. Issues raised or cleared since the previous check are returned as
+/// [`HealthEvent`]s; on the first check every issue counts as raised. The issues of a charger
+/// which is gone are cleared, unless its site couldn't be checked.
+pub struct HealthMonitor {
+    thresholds: HealthThresholds,
+    previous: HashMap<String, (SiteId, Vec<HealthIssue>)>,
+}
+
+impl HealthMonitor {
+    pub fn new(thresholds: HealthThresholds) -> Self {
+        Self {
+            thresholds,
+            previous: HashMap::new(),
+        }
+    }
+
+    pub async fn check(&mut self, client: &Client) -> Result<(HealthReport, Vec<HealthEvent>)> {
+        let mut chargers = Vec::new();
+        let mut failed_sites = Vec::new();
+
+        let mut sites = GetSites::default().pages();
+        while let Some(site) = sites.next(client).await? {
+            let site = match GetSite(site.id).send(client).await {
+                Ok(site) => site,
+                Err(err) => {
+                    warn!(site_id = %site.id, "checking site failed: {err}");
+                    failed_sites.push(SiteCheckFailed {
+                        site_id: site.id,
+                        reason: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            for charger in site.circuits.iter().flat_map(|c| &c.chargers) {
+                chargers.push(self.check_charger(client, site.id, &charger.id).await);
+            }
+        }
+
+        let report = HealthReport {
+            checked_at: DateTime::now_utc(),
+            chargers,
+            failed_sites,
+        };
+        let events = self.changes(&report);
+
+        Ok((report, events))
+    }
+
+    async fn check_charger(
+        &self,
+        client: &Client,
+        site_id: SiteId,
+        charger_id: &str,
+    ) -> ChargerHealth {
+        let now = DateTime::now_utc();
+
+        let state = match GetChargerState::new(charger_id).send(client).await {
+            Ok(state) => state,
+            Err(err) => {
+                warn!(charger_id, "checking charger health failed: {err}");
+
+                return ChargerHealth {
+                    charger_id: charger_id.to_string(),
+                    site_id,
+                    online: false,
+                    last_seen: None,
+                    issues: vec![HealthIssue::CheckFailed {
+                        reason: err.to_string(),
+                    }],
+                };
+            }
+        };
+
+        let from = DateTime(now.0 - self.thresholds.reboot_window);
+        let observations = GetObservations::new(
+            charger_id,
+            [ObservationId::RebootReason, ObservationId::TempMax],
+            from,
+            now,
+        )
+        .send(client)
+        .await
+        .unwrap_or_else(|err| {
+            // Still worth reporting what the state tells
+            debug!(charger_id, "fetching observations failed: {err}");
+            Observations::new()
+        });
+
+        assess(
+            charger_id,
+            site_id,
+            &state,
+            &observations,
+            &self.thresholds,
+            now,
+        )
+    }
+
+    fn changes(&mut self, report: &HealthReport) -> Vec<HealthEvent> {
+        let mut events = Vec::new();
+        let mut previous = mem::take(&mut self.previous);
+
+        for charger in &report.chargers {
+            let before = previous
+                .remove(&charger.charger_id)
+                .map(|(_, issues)| issues)
+                .unwrap_or_default();
+            let event = |change| HealthEvent {
+                charger_id: charger.charger_id.clone(),
+                change,
+            };
+
+            events.extend(
+                charger
+                    .issues
+                    .iter()
+                    .filter(|issue| !before.iter().any(|b| b.same_issue(issue)))
+                    .map(|issue| event(HealthChange::Raised(issue.clone()))),
+            );
+            events.extend(
+                before
+                    .iter()
+                    .filter(|b| !charger.issues.iter().any(|issue| issue.same_issue(b)))
+                    .map(|b| event(HealthChange::Cleared(b.clone()))),
+            );
+
+            self.previous.insert(
+                charger.charger_id.clone(),
+                (charger.site_id, charger.issues.clone()),
+            );
+        }
+
+        for (charger_id, (site_id, issues)) in previous {
+            if report.failed_sites.iter().any(|f| f.site_id == site_id) {
+                // Not gone, just not checked this time
+                self.previous.insert(charger_id, (site_id, issues));
+                continue;
+            }
+
+            events.extend(issues.into_iter().map(|issue| HealthEvent {
+                charger_id: charger_id.clone(),
+                change: HealthChange::Cleared(issue),
+            }));
+        }
+
+        events
+    }
+}
+
+fn assess(
+    charger_id: &str,
+    site_id: SiteId,
+    state: &ChargerState,
+    observations: &Observations,
+    thresholds: &HealthThresholds,
+    now: DateTime,
+) -> ChargerHealth {
+    let mut issues = Vec::new();
+
+    let last_seen = state.latest_pulse;
+    let online = match last_seen {
+        Some(seen) => now.0 - seen.0 < thresholds.offline_after && state.is_online != Some(false),
+        None => state.is_online == Some(true),
+    };
+
+    if !online {
+        issues.push(HealthIssue::Offline { last_seen });
+    }
+
+    if let Some(code) = state.error_code.filter(|code| *code != 0) {
+        issues.push(HealthIssue::Error { code });
+    }
+    if let Some(code) = state.fatal_error_code.filter(|code| *code != 0) {
+        issues.push(HealthIssue::FatalError { code });
+    }
+
+    let reboots = observations
+        .get(&ObservationId::RebootReason)
+        .map_or(0, Vec::len);
+    if reboots > thresholds.max_reboots {
+        issues.push(HealthIssue::RepeatedReboots { count: reboots });
+    }
+
+    // Chargers use either wifi or cellular, the one not in use is missing or 0
+    let low_signal = match (state.wifi_rssi, state.cell_rssi) {
+        (Some(wifi), _) if wifi != 0 => (wifi < thresholds.min_wifi_rssi).then_some(wifi),
+        (_, Some(cell)) if cell != 0 => (cell < thresholds.min_cell_rssi).then_some(cell),
+        _ => None,
+    };
+    if let Some(rssi) = low_signal {
+        issues.push(HealthIssue::LowSignal { rssi });
+    }
+
+    let temperature = observations
+        .get(&ObservationId::TempMax)
+        .and_then(|points| points.last())
+        .and_then(|point| point.value.as_f64());
+    if let Some(celsius) = temperature.filter(|t| *t > thresholds.max_temperature) {
+        issues.push(HealthIssue::HighTemperature { celsius });
+    }
+
+    ChargerHealth {
+        charger_id: charger_id.to_string(),
+        site_id,
+        online,
+        last_seen,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ObservationPoint, ObservationValue};
+
+    fn state(json: serde_json::Value) -> ChargerState {
+        let mut base = serde_json::json!({
+            "chargerOpMode": 1,
+            "isOnline": true,
+            "latestPulse": "2023-08-20T11:59:00Z",
+        });
+        base.as_object_mut()
+            .expect("object")
+            .extend(json.as_object().expect("object").clone());
+
+        serde_json::from_value(base).expect("state")
+    }
+
+    fn now() -> DateTime {
+        "2023-08-20T12:00:00Z".parse().expect("now")
+    }
+
+    fn assess_state(state: &ChargerState, observations: &Observations) -> ChargerHealth {
+        let thresholds = HealthThresholds::default();
+        assess(
+            "EC3VJ7GU",
+            SiteId(1),
+            state,
+            observations,
+            &thresholds,
+            now(),
+        )
+    }
+
+    #[test]
+    fn healthy_and_offline() {
+        let healthy = assess_state(
+            &state(serde_json::json!({"wiFiRSSI": -60})),
+            &Observations::new(),
+        );
+        assert!(healthy.is_healthy(), "{healthy:?}");
+
+        let stale = state(serde_json::json!({"latestPulse": "2023-08-20T10:00:00Z"}));
+        let offline = assess_state(&stale, &Observations::new());
+        assert!(!offline.online);
+        assert!(matches!(
+            offline.issues[..],
+            [HealthIssue::Offline { last_seen: Some(_) }]
+        ));
+    }
+
+    #[test]
+    fn thresholds_exceeded() {
+        let point = |value| ObservationPoint {
+            timestamp: now(),
+            value,
+        };
+
+        let mut observations = Observations::new();
+        observations.insert(
+            ObservationId::RebootReason,
+            vec![point(ObservationValue::Integer(1)); 4],
+        );
+        observations.insert(
+            ObservationId::TempMax,
+            vec![point(ObservationValue::Double(82.5))],
+        );
+
+        let health = assess_state(
+            &state(serde_json::json!({"errorCode": 11, "wiFiRSSI": -88})),
+            &observations,
+        );
+
+        assert_eq!(
+            health.issues,
+            vec![
+                HealthIssue::Error { code: 11 },
+                HealthIssue::RepeatedReboots { count: 4 },
+                HealthIssue::LowSignal { rssi: -88 },
+                HealthIssue::HighTemperature { celsius: 82.5 },
+            ]
+        );
+    }
+
+    fn charger(issues: Vec<HealthIssue>) -> ChargerHealth {
+        ChargerHealth {
+            charger_id: "EC3VJ7GU".into(),
+            site_id: SiteId(1),
+            online: true,
+            last_seen: None,
+            issues,
+        }
+    }
+
+    #[test]
+    fn raised_and_cleared() {
+        let mut monitor = HealthMonitor::new(HealthThresholds::default());
+        let report = |issues| HealthReport {
+            checked_at: now(),
+            chargers: vec![charger(issues)],
+            failed_sites: vec![],
+        };
+
+        let events = monitor.changes(&report(vec![HealthIssue::HighTemperature {
+            celsius: 80.0,
+        }]));
+        assert!(matches!(events[0].change, HealthChange::Raised(_)));
+
+        // Still too hot, just a different reading
+        let events = monitor.changes(&report(vec![HealthIssue::HighTemperature {
+            celsius: 81.0,
+        }]));
+        assert!(events.is_empty());
+
+        let events = monitor.changes(&report(vec![]));
+        assert_eq!(
+            events[0].change,
+            HealthChange::Cleared(HealthIssue::HighTemperature { celsius: 81.0 })
+        );
+
+        monitor.changes(&report(vec![HealthIssue::Error { code: 11 }]));
+        let events = monitor.changes(&report(vec![HealthIssue::Error { code: 12 }]));
+        assert_eq!(
+            events.iter().map(|e| &e.change).collect::<Vec<_>>(),
+            [
+                &HealthChange::Raised(HealthIssue::Error { code: 12 }),
+                &HealthChange::Cleared(HealthIssue::Error { code: 11 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn gone_and_unchecked_chargers() {
+        let mut monitor = HealthMonitor::new(HealthThresholds::default());
+        let offline = HealthIssue::Offline { last_seen: None };
+
+        monitor.changes(&HealthReport {
+            checked_at: now(),
+            chargers: vec![charger(vec![offline.clone()])],
+            failed_sites: vec![],
+        });
+
+        // The site failed, so the charger's issue is neither cleared nor forgotten
+        let events = monitor.changes(&HealthReport {
+            checked_at: now(),
+            chargers: vec![],
+            failed_sites: vec![SiteCheckFailed {
+                site_id: SiteId(1),
+                reason: "503".into(),
+            }],
+        });
+        assert!(events.is_empty());
+
+        // The charger is gone from a site which was checked
+        let events = monitor.changes(&HealthReport {
+            checked_at: now(),
+            chargers: vec![],
+            failed_sites: vec![],
+        });
+        assert_eq!(
+            events,
+            vec![HealthEvent {
+                charger_id: "EC3VJ7GU".into(),
+                change: HealthChange::Cleared(offline),
+            }]
+        );
+        assert!(monitor.previous.is_empty());
+    }
+}
@@ -1,7 +1,15 @@
 mod client;
+pub mod firmware;
 pub mod from_str;
+pub mod health;
 mod models;
 pub mod requests;
+pub mod session_tracker;
+#[cfg(feature = "streaming")]
+pub mod streaming;
+#[cfg(test)]
+mod test_server;
+pub mod watcher;
 
 pub use {client::*, models::*};
 
@@ -41,6 +49,18 @@ pub enum Error {
         body: String,
     },
 
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("io: {0}")]
+    Io(String),
+
+    #[error("streaming: {0}")]
+    Streaming(String),
+
     #[error("invalid access token: {0}")]
     AccessTokenParse(#[from] client::auth::ParseError),
 }
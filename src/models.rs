@@ -1,23 +1,51 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
+mod access;
+mod authorization_token;
+mod charge_plan;
 mod charger_session;
+mod charger_state;
+mod consumption;
+mod equalizer;
+mod observation;
 mod raw_session;
+mod usage;
 
 pub mod datetime;
 
+pub(crate) use authorization_token::strip_tag_prefix;
 pub(crate) use raw_session::RawSession;
-pub use {charger_session::*, datetime::DateTime};
+pub use {
+    access::*, authorization_token::*, charge_plan::*, charger_session::*, charger_state::*,
+    consumption::*, datetime::DateTime, equalizer::*, observation::*, usage::*,
+};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash)]
 pub struct SiteId(pub i64);
 
+impl fmt::Display for SiteId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash)]
+pub struct CircuitId(pub i64);
+
+impl fmt::Display for CircuitId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Site {
     pub id: SiteId,
     pub site_key: String,
     pub name: String,
-    pub level_of_access: Option<u8>, // 1 - 3
+    pub level_of_access: Option<AccessLevel>,
     pub address: Address,
     pub contact_info: Option<ContactInfo>,
 
@@ -40,21 +68,53 @@ pub struct Site {
     pub created_on: DateTime,
 
     pub updated_on: DateTime,
-    pub user_role: Option<u8>, // 1, 2, 3, 20
+    pub user_role: Option<UserSiteRole>,
     #[serde(default = "Vec::new")]
     pub allowed_site_actions: Vec<String>,
 }
 
+impl Site {
+    /// Returns the circuit the given charger is connected to, if it belongs to this site
+    pub fn circuit_for_charger(&self, charger_id: &str) -> Option<&Circuit> {
+        self.circuits
+            .iter()
+            .find(|circuit| circuit.chargers.iter().any(|c| c.id == charger_id))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SiteSub {
     pub id: SiteId,
     pub site_key: String,
     pub name: String,
-    pub level_of_access: Option<u8>, // 1 - 3
+    pub level_of_access: Option<AccessLevel>,
     pub address: Address,
 }
 
+/// A site together with everything installed on it, as returned by `api/accounts/products`
+///
+/// The rest of the site details, like its rated current, are left out. Use `GetSite` for the
+/// requests validated against a `Site`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteProducts {
+    #[serde(flatten)]
+    pub site: SiteSub,
+    #[serde(default = "Vec::new")]
+    pub circuits: Vec<Circuit>,
+    #[serde(default = "Vec::new")]
+    pub equalizers: Vec<Equalizer>,
+}
+
+impl SiteProducts {
+    pub fn chargers(&self) -> impl Iterator<Item = &Charger> {
+        self.circuits
+            .iter()
+            .flat_map(|circuit| circuit.chargers.iter())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Address {
@@ -89,8 +149,8 @@ pub struct ContactInfo {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Circuit {
-    pub id: i64,
-    pub site_id: i64,
+    pub id: CircuitId,
+    pub site_id: SiteId,
     pub circuit_panel_id: i64,
     pub panel_name: String,
     pub rated_current: f64,
@@ -109,9 +169,9 @@ pub struct Charger {
     pub created_on: String,
     pub updated_on: String,
     pub back_plate: BackPlate,
-    pub level_of_access: Option<u8>, // 1 - 3
-    pub product_code: u32,           // 1, 100, 1000
-    pub user_role: Option<u8>,       // 1, 2, 3, 20
+    pub level_of_access: Option<AccessLevel>,
+    pub product_code: u32, // 1, 100, 1000
+    pub user_role: Option<UserSiteRole>,
     pub is_temporary: bool,
 }
 
@@ -129,8 +189,8 @@ pub struct BackPlate {
 pub struct Equalizer {
     pub id: String,
     pub name: String,
-    pub site_id: i32,
-    pub circuit_id: Option<i32>,
+    pub site_id: SiteId,
+    pub circuit_id: Option<CircuitId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -148,6 +208,13 @@ pub struct Profile {
 
     #[serde(rename = "lastName")]
     pub lastname: String,
+
+    pub locale: Option<String>,
+    pub is_email_verified: Option<bool>,
+    pub is_phone_verified: Option<bool>,
+    pub accepted_terms_version: Option<String>,
+    pub accepted_terms_at: Option<DateTime>,
+    pub created_on: Option<DateTime>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,80 @@
+/// Who is allowed to charge on a charger or site (`levelOfAccess`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(from = "u8", into = "u8")]
+pub enum AccessLevel {
+    OpenForAll,
+    EaseeAccountRequired,
+    Whitelist,
+    Unknown(u8),
+}
+
+impl From<u8> for AccessLevel {
+    fn from(n: u8) -> Self {
+        match n {
+            1 => Self::OpenForAll,
+            2 => Self::EaseeAccountRequired,
+            3 => Self::Whitelist,
+            n => Self::Unknown(n),
+        }
+    }
+}
+
+impl From<AccessLevel> for u8 {
+    fn from(level: AccessLevel) -> u8 {
+        match level {
+            AccessLevel::OpenForAll => 1,
+            AccessLevel::EaseeAccountRequired => 2,
+            AccessLevel::Whitelist => 3,
+            AccessLevel::Unknown(n) => n,
+        }
+    }
+}
+
+/// The role a user has on a site or charger (`userRole` / `roleId`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(from = "u8", into = "u8")]
+pub enum UserSiteRole {
+    User,
+    Admin,
+    Owner,
+    Partner,
+    Unknown(u8),
+}
+
+impl From<u8> for UserSiteRole {
+    fn from(n: u8) -> Self {
+        match n {
+            1 => Self::User,
+            2 => Self::Admin,
+            3 => Self::Owner,
+            20 => Self::Partner,
+            n => Self::Unknown(n),
+        }
+    }
+}
+
+impl From<UserSiteRole> for u8 {
+    fn from(role: UserSiteRole) -> u8 {
+        match role {
+            UserSiteRole::User => 1,
+            UserSiteRole::Admin => 2,
+            UserSiteRole::Owner => 3,
+            UserSiteRole::Partner => 20,
+            UserSiteRole::Unknown(n) => n,
+        }
+    }
+}
+
+/// A user with access to a site
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteUser {
+    pub user_id: i64,
+    pub name: Option<String>,
+    #[serde(alias = "eMail")]
+    pub email: Option<String>,
+    #[serde(rename = "phoneNo", alias = "phone")]
+    pub phone_nr: Option<String>,
+    #[serde(rename = "roleId", alias = "role")]
+    pub role: UserSiteRole,
+}
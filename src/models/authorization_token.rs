@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use super::{ChargerSession, DateTime};
+
+/// An RFID tag (or other authorization token) allowed to charge
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationToken {
+    #[serde(alias = "rfidTag", alias = "token")]
+    pub tag: String,
+    pub name: Option<String>,
+    pub user_id: Option<i64>,
+    pub user_name: Option<String>,
+    #[serde(alias = "eMail")]
+    pub email: Option<String>,
+    pub created_on: Option<DateTime>,
+}
+
+/// Lookup from authorization token to the user it belongs to,
+/// so that `ChargerSession::auth_token` can be attributed to a person.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorizationTokens(HashMap<String, AuthorizationToken>);
+
+impl AuthorizationTokens {
+    pub fn new(tokens: impl IntoIterator<Item = AuthorizationToken>) -> Self {
+        Self(
+            tokens
+                .into_iter()
+                .map(|token| (normalize(&token.tag), token))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, tag: &str) -> Option<&AuthorizationToken> {
+        self.0.get(&normalize(tag))
+    }
+
+    /// The token used to authorize the session, if it is known
+    pub fn for_session(&self, session: &ChargerSession) -> Option<&AuthorizationToken> {
+        session.auth_token.as_deref().and_then(|tag| self.get(tag))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &AuthorizationToken> {
+        self.0.values()
+    }
+}
+
+impl FromIterator<AuthorizationToken> for AuthorizationTokens {
+    fn from_iter<I: IntoIterator<Item = AuthorizationToken>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+/// Sessions report tags as eg `nfc-04A2B3C4D5` while the token lists use `04a2b3c4d5`.
+fn normalize(tag: &str) -> String {
+    strip_tag_prefix(tag).to_ascii_uppercase()
+}
+
+/// The tag as the token lists know it, without whitespace and the `nfc-` prefix of sessions
+pub(crate) fn strip_tag_prefix(tag: &str) -> &str {
+    let tag = tag.trim();
+    match tag.get(..4) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("nfc-") => &tag[4..],
+        _ => tag,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_session() {
+        let tokens = serde_json::from_str::<Vec<AuthorizationToken>>(
+            r#"
+[
+  { "rfidTag": "04a2b3c4d5", "name": "Blue tag", "userId": 265514, "userName": "Test Testsson", "email": null, "createdOn": "2023-01-12T14:44:57.04698" },
+  { "rfidTag": "0011223344", "name": null, "userId": null, "userName": null, "email": null, "createdOn": null }
+]
+"#,
+        )
+        .expect("deserializing")
+        .into_iter()
+        .collect::<AuthorizationTokens>();
+
+        let session = serde_json::from_str::<ChargerSession>(
+            r#"
+{
+  "carConnected": "2023-01-20T19:31:46Z",
+  "carDisconnected": "2023-01-20T19:35:28Z",
+  "kiloWattHours": 0.581242,
+  "pricePerKwhExcludingVat": 0.0,
+  "pricePrKwhIncludingVat": 0.0,
+  "costExcludingVat": 0.0,
+  "costIncludingVat": 0.0,
+  "id": 4,
+  "authToken": "nfc-04A2B3C4D5"
+}
+"#,
+        )
+        .expect("deserializing session");
+
+        let token = tokens.for_session(&session).expect("should be attributed");
+        assert_eq!(token.user_id, Some(265514));
+        assert!(tokens.get("5566778899").is_none());
+    }
+}
@@ -0,0 +1,264 @@
+use std::{fmt, str};
+
+use super::{DateTime, datetime};
+
+/// A single charging window, optionally repeated every day
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BasicChargePlan {
+    pub id: Option<String>,
+    #[serde(serialize_with = "datetime::serialize_zulu")]
+    pub charge_start_time: DateTime,
+    #[serde(serialize_with = "datetime::serialize_zulu")]
+    pub charge_stop_time: DateTime,
+    pub repeat: bool,
+    pub is_enabled: bool,
+    pub time_zone: Option<String>,
+    pub charging_current_limit: Option<f64>,
+}
+
+/// Charging windows per day of the week
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyChargePlan {
+    pub id: Option<String>,
+    pub is_enabled: bool,
+    pub time_zone: Option<String>,
+    #[serde(default = "Vec::new")]
+    pub days: Vec<ChargePlanDay>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChargePlanDay {
+    pub day_of_week: Weekday,
+    #[serde(default = "Vec::new")]
+    pub ranges: Vec<ChargePlanRange>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChargePlanRange {
+    pub start_time: TimeOfDay,
+    pub stop_time: TimeOfDay,
+    pub charging_current_limit: Option<f64>,
+}
+
+/// Day of week as numbered by the api, which is .NET's `System.DayOfWeek`: sunday is 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Weekday {
+    Sunday,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+}
+
+impl TryFrom<u8> for Weekday {
+    type Error = String;
+
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        match n {
+            0 => Ok(Self::Sunday),
+            1 => Ok(Self::Monday),
+            2 => Ok(Self::Tuesday),
+            3 => Ok(Self::Wednesday),
+            4 => Ok(Self::Thursday),
+            5 => Ok(Self::Friday),
+            6 => Ok(Self::Saturday),
+            n => Err(format!("invalid day of week: {n}")),
+        }
+    }
+}
+
+impl From<Weekday> for u8 {
+    fn from(day: Weekday) -> u8 {
+        day as u8
+    }
+}
+
+impl From<time::Weekday> for Weekday {
+    fn from(day: time::Weekday) -> Self {
+        match day {
+            time::Weekday::Monday => Self::Monday,
+            time::Weekday::Tuesday => Self::Tuesday,
+            time::Weekday::Wednesday => Self::Wednesday,
+            time::Weekday::Thursday => Self::Thursday,
+            time::Weekday::Friday => Self::Friday,
+            time::Weekday::Saturday => Self::Saturday,
+            time::Weekday::Sunday => Self::Sunday,
+        }
+    }
+}
+
+impl From<Weekday> for time::Weekday {
+    fn from(day: Weekday) -> Self {
+        match day {
+            Weekday::Monday => Self::Monday,
+            Weekday::Tuesday => Self::Tuesday,
+            Weekday::Wednesday => Self::Wednesday,
+            Weekday::Thursday => Self::Thursday,
+            Weekday::Friday => Self::Friday,
+            Weekday::Saturday => Self::Saturday,
+            Weekday::Sunday => Self::Sunday,
+        }
+    }
+}
+
+pub static FORMAT_TIME_OF_DAY: &[time::format_description::FormatItem<'static>] =
+    time::macros::format_description!("[hour]:[minute]");
+
+/// Wall clock time formatted as `22:00`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeOfDay(pub time::Time);
+
+impl str::FromStr for TimeOfDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        time::Time::parse(s, FORMAT_TIME_OF_DAY)
+            .map(Self)
+            .map_err(|err| format!("parsing `{s}` as time of day: {err}"))
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = self.0.format(FORMAT_TIME_OF_DAY).expect("Timeformat");
+        f.write_str(&s)
+    }
+}
+
+impl serde::Serialize for TimeOfDay {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TimeOfDay {
+    fn deserialize<D>(des: D) -> Result<TimeOfDay, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::from_str::deserialize(des)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T>(s: &str)
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let des = serde_json::from_str::<T>(s).expect("deserializing");
+        let ser = serde_json::to_value(&des).expect("serializing");
+
+        assert_eq!(
+            ser,
+            serde_json::from_str::<serde_json::Value>(s).expect("parsing")
+        );
+    }
+
+    #[test]
+    fn basic_charge_plan_round_trip() {
+        round_trip::<BasicChargePlan>(
+            r#"
+{
+  "id": "EC3VJ7GU",
+  "chargeStartTime": "2023-08-20T22:00:00Z",
+  "chargeStopTime": "2023-08-21T06:00:00Z",
+  "repeat": true,
+  "isEnabled": true,
+  "timeZone": "Europe/Stockholm",
+  "chargingCurrentLimit": 16.0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn weekly_charge_plan_round_trip() {
+        round_trip::<WeeklyChargePlan>(
+            r#"
+{
+  "id": "EC3VJ7GU",
+  "isEnabled": true,
+  "timeZone": "Europe/Stockholm",
+  "days": [
+    {
+      "dayOfWeek": 0,
+      "ranges": [
+        { "startTime": "00:00", "stopTime": "06:00", "chargingCurrentLimit": null },
+        { "startTime": "22:00", "stopTime": "23:59", "chargingCurrentLimit": 10.0 }
+      ]
+    },
+    {
+      "dayOfWeek": 6,
+      "ranges": []
+    }
+  ]
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn nulls_round_trip() {
+        round_trip::<BasicChargePlan>(
+            r#"
+{
+  "id": null,
+  "chargeStartTime": "2023-08-20T22:00:00Z",
+  "chargeStopTime": "2023-08-21T06:00:00Z",
+  "repeat": false,
+  "isEnabled": false,
+  "timeZone": null,
+  "chargingCurrentLimit": null
+}
+"#,
+        );
+
+        round_trip::<WeeklyChargePlan>(
+            r#"
+{
+  "id": null,
+  "isEnabled": true,
+  "timeZone": null,
+  "days": [
+    {
+      "dayOfWeek": 1,
+      "ranges": [{ "startTime": "22:00", "stopTime": "23:59", "chargingCurrentLimit": null }]
+    }
+  ]
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(
+            serde_json::from_str::<Weekday>("0").unwrap(),
+            Weekday::Sunday
+        );
+        assert_eq!(
+            serde_json::from_str::<Weekday>("1").unwrap(),
+            Weekday::Monday
+        );
+        assert_eq!(serde_json::to_string(&Weekday::Saturday).unwrap(), "6");
+        assert_eq!(time::Weekday::from(Weekday::Sunday), time::Weekday::Sunday);
+    }
+
+    #[test]
+    fn invalid_weekday() {
+        serde_json::from_str::<Weekday>("7").expect_err("only 0-6 are valid");
+    }
+}
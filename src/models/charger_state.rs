@@ -0,0 +1,173 @@
+use super::DateTime;
+
+/// Snapshot of a charger as returned by `api/chargers/{id}/state`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChargerState {
+    pub charger_op_mode: ChargerOpMode,
+    pub is_online: Option<bool>,
+    pub smart_charging: Option<bool>,
+    pub cable_locked: Option<bool>,
+
+    /// kW
+    pub total_power: Option<f64>,
+    /// kWh
+    pub session_energy: Option<f64>,
+    /// kWh
+    pub lifetime_energy: Option<f64>,
+    pub energy_per_hour: Option<f64>,
+
+    pub output_current: Option<f64>,
+    pub output_phase: Option<i32>,
+    pub voltage: Option<f64>,
+
+    pub in_current_t2: Option<f64>,
+    pub in_current_t3: Option<f64>,
+    pub in_current_t4: Option<f64>,
+    pub in_current_t5: Option<f64>,
+
+    pub dynamic_charger_current: Option<f64>,
+    pub dynamic_circuit_current_p1: Option<f64>,
+    pub dynamic_circuit_current_p2: Option<f64>,
+    pub dynamic_circuit_current_p3: Option<f64>,
+
+    #[serde(rename = "wiFiRSSI")]
+    pub wifi_rssi: Option<i32>,
+    #[serde(rename = "cellRSSI")]
+    pub cell_rssi: Option<i32>,
+
+    pub latest_pulse: Option<DateTime>,
+    pub charger_firmware: Option<i32>,
+    pub latest_firmware: Option<i32>,
+
+    pub error_code: Option<i32>,
+    pub fatal_error_code: Option<i32>,
+    pub reason_for_no_current: Option<i32>,
+}
+
+/// The chargers current operating mode (`chargerOpMode`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(from = "u8", into = "u8")]
+pub enum ChargerOpMode {
+    Offline,
+    Disconnected,
+    AwaitingStart,
+    Charging,
+    Completed,
+    Error,
+    ReadyToCharge,
+    AwaitingAuthentication,
+    Deauthenticating,
+    Unknown(u8),
+}
+
+impl From<u8> for ChargerOpMode {
+    fn from(n: u8) -> Self {
+        match n {
+            0 => Self::Offline,
+            1 => Self::Disconnected,
+            2 => Self::AwaitingStart,
+            3 => Self::Charging,
+            4 => Self::Completed,
+            5 => Self::Error,
+            6 => Self::ReadyToCharge,
+            7 => Self::AwaitingAuthentication,
+            8 => Self::Deauthenticating,
+            n => Self::Unknown(n),
+        }
+    }
+}
+
+impl From<ChargerOpMode> for u8 {
+    fn from(mode: ChargerOpMode) -> u8 {
+        match mode {
+            ChargerOpMode::Offline => 0,
+            ChargerOpMode::Disconnected => 1,
+            ChargerOpMode::AwaitingStart => 2,
+            ChargerOpMode::Charging => 3,
+            ChargerOpMode::Completed => 4,
+            ChargerOpMode::Error => 5,
+            ChargerOpMode::ReadyToCharge => 6,
+            ChargerOpMode::AwaitingAuthentication => 7,
+            ChargerOpMode::Deauthenticating => 8,
+            ChargerOpMode::Unknown(n) => n,
+        }
+    }
+}
+
+/// The subset of `api/chargers/{id}/config` we care about
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChargerConfig {
+    pub is_enabled: Option<bool>,
+    pub authorization_required: Option<bool>,
+    pub max_charger_current: Option<f64>,
+    pub phase_mode: Option<i32>,
+    pub offline_charging_mode: Option<i32>,
+    pub wi_fi_ssid: Option<String>,
+}
+
+/// Reply received when a command has been accepted by the API
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandResponse {
+    pub device: String,
+    pub command_id: i64,
+    pub ticks: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_state() {
+        let s = r#"
+{
+  "smartCharging": false,
+  "cableLocked": true,
+  "chargerOpMode": 3,
+  "totalPower": 11.04,
+  "sessionEnergy": 4.213,
+  "energyPerHour": 0.0,
+  "wiFiRSSI": -62,
+  "cellRSSI": -79,
+  "localRSSI": null,
+  "outputPhase": 30,
+  "dynamicCircuitCurrentP1": 32.0,
+  "dynamicCircuitCurrentP2": 32.0,
+  "dynamicCircuitCurrentP3": 32.0,
+  "latestPulse": "2023-08-20T12:10:05Z",
+  "chargerFirmware": 302,
+  "latestFirmware": 302,
+  "voltage": 232.1,
+  "chargerRAT": 1,
+  "lockCablePermanently": false,
+  "inCurrentT2": 16.0,
+  "inCurrentT3": 16.0,
+  "inCurrentT4": 16.0,
+  "inCurrentT5": 0.0,
+  "outputCurrent": 16.0,
+  "isOnline": true,
+  "inVoltageT1T2": 232.1,
+  "lifetimeEnergy": 2345.67,
+  "errorCode": 0,
+  "fatalErrorCode": 0,
+  "dynamicChargerCurrent": 16.0,
+  "reasonForNoCurrent": 0
+}
+"#;
+
+        let state = serde_json::from_str::<ChargerState>(s).expect("deserializing");
+        assert_eq!(state.charger_op_mode, ChargerOpMode::Charging);
+        assert_eq!(state.wifi_rssi, Some(-62));
+        assert_eq!(state.dynamic_charger_current, Some(16.0));
+    }
+
+    #[test]
+    fn unknown_op_mode_round_trips() {
+        let mode = serde_json::from_str::<ChargerOpMode>("42").expect("deserializing");
+        assert_eq!(mode, ChargerOpMode::Unknown(42));
+        assert_eq!(serde_json::to_string(&mode).expect("serializing"), "42");
+    }
+}
@@ -0,0 +1,46 @@
+use super::{DateTime, SiteId};
+
+/// Total consumption of a site during `[from - to[`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteConsumption {
+    pub site_id: SiteId,
+    pub from: DateTime,
+    pub to: DateTime,
+    #[serde(rename = "totalEnergy")]
+    pub kwh: f64,
+    pub cost_excluding_vat: Option<f64>,
+    pub cost_including_vat: Option<f64>,
+    #[serde(rename = "currencyId")]
+    pub currency: Option<String>,
+    pub session_count: Option<u32>,
+}
+
+/// Consumption of a single user on a site
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserConsumption {
+    pub user_id: Option<i64>,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    #[serde(rename = "phoneNo")]
+    pub phone_nr: Option<String>,
+    #[serde(rename = "totalEnergy")]
+    pub kwh: f64,
+    pub cost_excluding_vat: Option<f64>,
+    pub cost_including_vat: Option<f64>,
+    pub session_count: Option<u32>,
+}
+
+/// Consumption on a single charger of a site
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChargerConsumption {
+    pub charger_id: String,
+    pub charger_name: Option<String>,
+    #[serde(rename = "totalEnergy")]
+    pub kwh: f64,
+    pub cost_excluding_vat: Option<f64>,
+    pub cost_including_vat: Option<f64>,
+    pub session_count: Option<u32>,
+}
@@ -25,6 +25,14 @@ impl DateTime {
     pub fn from_unix_timestamp(epoch: i64) -> Result<Self, time::error::ComponentRange> {
         time::OffsetDateTime::from_unix_timestamp(epoch).map(Self)
     }
+
+    /// Formats as `2023-01-20T19:35:28Z`
+    pub fn format_zulu(&self) -> String {
+        self.0
+            .to_offset(time::UtcOffset::UTC)
+            .format(FORMAT_Z)
+            .expect("Dateformat")
+    }
 }
 
 impl From<time::OffsetDateTime> for DateTime {
@@ -68,8 +76,9 @@ impl str::FromStr for DateTime {
             time::OffsetDateTime::parse(s, FORMAT_WITH_OFFSET)
                 .map_err(|err| format!("parsing `{s}` as offset datetime: {err}"))
                 .map(Self)
-        } else if s.ends_with('Z') {
-            time::PrimitiveDateTime::parse(s, FORMAT_Z)
+        } else if let Some(without_z) = s.strip_suffix('Z') {
+            // The streaming hub sends zulu time with fractions, `2023-01-20T19:35:28.123Z`
+            time::PrimitiveDateTime::parse(without_z, FORMAT)
                 .map_err(|err| format!("parsing `{s}` as datetime zulu format: {err}"))
                 .map(|dt| Self(dt.assume_utc()))
         } else {
@@ -97,6 +106,14 @@ impl serde::Serialize for DateTime {
     }
 }
 
+/// Serializes as `2023-01-20T19:35:28Z`, for endpoints that expect (and return) zulu time.
+pub fn serialize_zulu<S>(dt: &DateTime, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&dt.format_zulu())
+}
+
 impl<'de> serde::Deserialize<'de> for DateTime {
     fn deserialize<D>(des: D) -> Result<DateTime, D::Error>
     where
@@ -166,6 +183,9 @@ mod tests {
     fn parse_easee_zulu_time() {
         "2023-01-20T19:31:46Z".parse::<DateTime>().expect("parsing");
         "2023-01-20T19:35:28Z".parse::<DateTime>().expect("parsing");
+        "2023-01-20T19:35:28.123Z"
+            .parse::<DateTime>()
+            .expect("parsing with fraction");
     }
 
     //#[test]
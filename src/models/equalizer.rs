@@ -0,0 +1,91 @@
+use super::DateTime;
+
+/// Snapshot of an equalizer as returned by `api/equalizers/{id}/state`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqualizerState {
+    pub is_online: Option<bool>,
+    pub latest_pulse: Option<DateTime>,
+
+    /// A
+    #[serde(rename = "currentL1")]
+    pub current_l1: Option<f64>,
+    #[serde(rename = "currentL2")]
+    pub current_l2: Option<f64>,
+    #[serde(rename = "currentL3")]
+    pub current_l3: Option<f64>,
+
+    /// V
+    #[serde(rename = "voltageNL1")]
+    pub voltage_nl1: Option<f64>,
+    #[serde(rename = "voltageNL2")]
+    pub voltage_nl2: Option<f64>,
+    #[serde(rename = "voltageNL3")]
+    pub voltage_nl3: Option<f64>,
+
+    /// kW
+    pub active_power_import: Option<f64>,
+    pub active_power_export: Option<f64>,
+    pub reactive_power_import: Option<f64>,
+    pub reactive_power_export: Option<f64>,
+    pub max_power_import: Option<f64>,
+
+    /// Meter reading in kWh
+    pub cumulative_active_power_import: Option<f64>,
+    pub cumulative_active_power_export: Option<f64>,
+
+    /// Signal strength
+    pub rcpi: Option<f64>,
+    pub software_release: Option<i32>,
+    pub latest_firmware: Option<i32>,
+}
+
+/// The configurable parts of an equalizer
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqualizerConfig {
+    pub meter_type: Option<i32>,
+    /// Rating of the buildings main fuse, A
+    pub main_fuse: Option<f64>,
+    /// Allow charging from excess solar production
+    pub surplus_charging: Option<bool>,
+    /// Max current the equalizer may hand out to chargers, A
+    pub max_allocated_current: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_state() {
+        let s = r#"
+{
+  "activePowerImport": 4.2,
+  "activePowerExport": 0.0,
+  "reactivePowerImport": 0.3,
+  "reactivePowerExport": 0.0,
+  "maxPowerImport": 11.4,
+  "voltageNL1": 231.2,
+  "voltageNL2": 232.0,
+  "voltageNL3": 230.9,
+  "voltageL1L2": 400.1,
+  "currentL1": 6.1,
+  "currentL2": 5.0,
+  "currentL3": 7.2,
+  "cumulativeActivePowerImport": 35123.4,
+  "cumulativeActivePowerExport": 12.0,
+  "isOnline": true,
+  "latestPulse": "2023-08-20T12:10:05Z",
+  "rcpi": -60.5,
+  "meterType": 2,
+  "softwareRelease": 2130,
+  "latestFirmware": 2130
+}
+"#;
+
+        let state = serde_json::from_str::<EqualizerState>(s).expect("deserializing");
+        assert_eq!(state.current_l3, Some(7.2));
+        assert_eq!(state.cumulative_active_power_import, Some(35123.4));
+    }
+}
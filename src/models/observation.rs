@@ -0,0 +1,307 @@
+use std::fmt;
+
+use super::DateTime;
+use crate::Error;
+
+macro_rules! observation_ids {
+    ($($(#[$meta:meta])* $name:ident = $n:literal,)*) => {
+        /// Identifies a value reported by a charger or equalizer, both in observation history
+        /// and on the streaming hub. Ids missing from the catalog are kept as `Unknown`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ObservationId {
+            $($(#[$meta])* $name,)*
+            Unknown(u16),
+        }
+
+        impl From<u16> for ObservationId {
+            fn from(n: u16) -> Self {
+                match n {
+                    $($n => Self::$name,)*
+                    n => Self::Unknown(n),
+                }
+            }
+        }
+
+        impl From<ObservationId> for u16 {
+            fn from(id: ObservationId) -> u16 {
+                match id {
+                    $(ObservationId::$name => $n,)*
+                    ObservationId::Unknown(n) => n,
+                }
+            }
+        }
+    };
+}
+
+observation_ids! {
+    SelfTestResult = 1,
+    WifiEvent = 10,
+    ChargerOfflineReason = 11,
+    LocalPreAuthorizeEnabled = 15,
+    LocalAuthorizeOfflineEnabled = 16,
+    AllowOfflineTxForUnknownId = 17,
+    BackplateType = 19,
+    SiteStructure = 20,
+    DetectedPowerGridType = 21,
+    CircuitMaxCurrentP1 = 22,
+    CircuitMaxCurrentP2 = 23,
+    CircuitMaxCurrentP3 = 24,
+    SiteIdNumeric = 27,
+    LockCablePermanently = 30,
+    IsEnabled = 31,
+    WifiSsid = 36,
+    EnableIdleCurrent = 37,
+    PhaseMode = 38,
+    LedStripBrightness = 40,
+    LocalAuthorizationRequired = 41,
+    AuthorizationRequired = 42,
+    RemoteStartRequired = 43,
+    SmartButtonEnabled = 44,
+    OfflineChargingMode = 45,
+    LedMode = 46,
+    MaxChargerCurrent = 47,
+    DynamicChargerCurrent = 48,
+    MaxCurrentOfflineFallbackP1 = 50,
+    MaxCurrentOfflineFallbackP2 = 51,
+    MaxCurrentOfflineFallbackP3 = 52,
+    ChargingSchedule = 62,
+    PairedEqualizer = 65,
+    NumberOfCarsConnected = 76,
+    NumberOfCarsCharging = 77,
+    SoftwareRelease = 80,
+    RebootReason = 89,
+    ReasonForNoCurrent = 96,
+    PilotMode = 100,
+    SmartCharging = 102,
+    CableLocked = 103,
+    CableRating = 104,
+    UserIdTokenReversed = 108,
+    ChargerOpMode = 109,
+    OutputPhase = 110,
+    DynamicCircuitCurrentP1 = 111,
+    DynamicCircuitCurrentP2 = 112,
+    DynamicCircuitCurrentP3 = 113,
+    OutputCurrent = 114,
+    DeratedCurrent = 115,
+    DeratingActive = 116,
+    ErrorCode = 119,
+    TotalPower = 120,
+    SessionEnergy = 121,
+    EnergyPerHour = 122,
+    LifetimeEnergy = 124,
+    LifetimeRelaySwitches = 125,
+    LifetimeHours = 126,
+    UserIdToken = 128,
+    ChargingSession = 129,
+    CellRssi = 130,
+    WifiRssi = 132,
+    LocalRssi = 136,
+    TempMax = 150,
+    TempAmbientPowerBoard = 151,
+    TempInputT2 = 152,
+    TempInputT3 = 153,
+    TempInputT4 = 154,
+    TempInputT5 = 155,
+    TempOutputN = 160,
+    TempOutputL1 = 161,
+    TempOutputL2 = 162,
+    TempOutputL3 = 163,
+    TempAmbient = 170,
+    LightAmbient = 171,
+    IntRelHumidity = 172,
+    BackPlateLocked = 173,
+    InCurrentT2 = 182,
+    InCurrentT3 = 183,
+    InCurrentT4 = 184,
+    InCurrentT5 = 185,
+    InVoltageT1T2 = 190,
+    InVoltageT1T3 = 191,
+    InVoltageT1T4 = 192,
+    InVoltageT1T5 = 193,
+    InVoltageT2T3 = 194,
+    InVoltageT2T4 = 195,
+    InVoltageT2T5 = 196,
+    InVoltageT3T4 = 197,
+    InVoltageT3T5 = 198,
+    InVoltageT4T5 = 199,
+    LteRsrp = 220,
+    LteSinr = 221,
+    LteRsrq = 222,
+    EqAvailableCurrentP1 = 230,
+    EqAvailableCurrentP2 = 231,
+    EqAvailableCurrentP3 = 232,
+}
+
+impl fmt::Display for ObservationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        u16::from(*self).fmt(f)
+    }
+}
+
+impl serde::Serialize for ObservationId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u16(u16::from(*self))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ObservationId {
+    fn deserialize<D>(des: D) -> Result<ObservationId, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <u16 as serde::Deserialize>::deserialize(des).map(ObservationId::from)
+    }
+}
+
+/// How an observation value is encoded (`dataType`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(from = "u8", into = "u8")]
+pub enum DataType {
+    Boolean,
+    Double,
+    Integer,
+    String,
+    Unknown(u8),
+}
+
+impl From<u8> for DataType {
+    fn from(n: u8) -> Self {
+        match n {
+            2 => Self::Boolean,
+            3 => Self::Double,
+            4 => Self::Integer,
+            6 => Self::String,
+            n => Self::Unknown(n),
+        }
+    }
+}
+
+impl From<DataType> for u8 {
+    fn from(data_type: DataType) -> u8 {
+        match data_type {
+            DataType::Boolean => 2,
+            DataType::Double => 3,
+            DataType::Integer => 4,
+            DataType::String => 6,
+            DataType::Unknown(n) => n,
+        }
+    }
+}
+
+/// An observed value, typed by what the API sent
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum ObservationValue {
+    Boolean(bool),
+    Integer(i64),
+    Double(f64),
+    String(String),
+}
+
+impl ObservationValue {
+    /// Converts a raw value as sent on the streaming hub, eg `"3.68"` with [`DataType::Double`]
+    pub fn from_raw(data_type: DataType, raw: &str) -> Result<Self, Error> {
+        let invalid = |err: &dyn fmt::Display| {
+            Error::InvalidResponse(format!("`{raw}` as {data_type:?} observation: {err}"))
+        };
+
+        match data_type {
+            DataType::Boolean => match raw.trim() {
+                "1" => Ok(Self::Boolean(true)),
+                "0" => Ok(Self::Boolean(false)),
+                s => s
+                    .to_ascii_lowercase()
+                    .parse()
+                    .map(Self::Boolean)
+                    .map_err(|err| invalid(&err)),
+            },
+            DataType::Integer => raw
+                .trim()
+                .parse()
+                .map(Self::Integer)
+                .map_err(|err| invalid(&err)),
+            DataType::Double => raw
+                .trim()
+                .parse()
+                .map(Self::Double)
+                .map_err(|err| invalid(&err)),
+            DataType::String | DataType::Unknown(_) => Ok(Self::String(raw.to_string())),
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ObservationValue::Integer(n) => Some(*n as f64),
+            ObservationValue::Double(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ObservationValue::Integer(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ObservationValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ObservationValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// One historic observation
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservationPoint {
+    pub timestamp: DateTime,
+    pub value: ObservationValue,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observation_id_roundtrip() {
+        for n in 0..=u16::MAX {
+            assert_eq!(u16::from(ObservationId::from(n)), n);
+        }
+
+        assert_eq!(ObservationId::from(109), ObservationId::ChargerOpMode);
+        assert_eq!(ObservationId::from(4242), ObservationId::Unknown(4242));
+    }
+
+    #[test]
+    fn values_from_raw() {
+        let value = |t: u8, raw| ObservationValue::from_raw(DataType::from(t), raw);
+
+        assert_eq!(value(2, "1").unwrap(), ObservationValue::Boolean(true));
+        assert_eq!(value(2, "False").unwrap(), ObservationValue::Boolean(false));
+        assert_eq!(value(3, "3.68").unwrap(), ObservationValue::Double(3.68));
+        assert_eq!(value(4, "3").unwrap(), ObservationValue::Integer(3));
+        assert_eq!(
+            value(6, "4.0.3").unwrap(),
+            ObservationValue::String("4.0.3".into())
+        );
+        assert_eq!(
+            value(7, "{}").unwrap(),
+            ObservationValue::String("{}".into())
+        );
+
+        assert!(value(4, "3.5").is_err());
+        assert!(value(2, "maybe").is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use super::DateTime;
+
+/// Energy used during `[from - to[`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBucket {
+    pub from: DateTime,
+    pub to: DateTime,
+    pub kwh: f64,
+    pub cost: Option<f64>,
+    pub currency: Option<String>,
+}
+
+impl UsageBucket {
+    pub(crate) fn month(year: i32, month: u8, kwh: f64) -> Result<Self, String> {
+        let month = time::Month::try_from(month).map_err(|err| format!("month {month}: {err}"))?;
+        let from = time::Date::from_calendar_date(year, month, 1)
+            .map_err(|err| format!("{year}-{month}: {err}"))?;
+
+        let (to_year, to_month) = match month {
+            time::Month::December => (year + 1, time::Month::January),
+            month => (year, month.next()),
+        };
+        let to = time::Date::from_calendar_date(to_year, to_month, 1)
+            .map_err(|err| format!("{to_year}-{to_month}: {err}"))?;
+
+        Ok(Self {
+            from: DateTime(from.midnight().assume_utc()),
+            to: DateTime(to.midnight().assume_utc()),
+            kwh,
+            cost: None,
+            currency: None,
+        })
+    }
+
+    pub(crate) fn year(year: i32, kwh: f64) -> Result<Self, String> {
+        let from = time::Date::from_calendar_date(year, time::Month::January, 1)
+            .map_err(|err| format!("{year}: {err}"))?;
+        let to = time::Date::from_calendar_date(year + 1, time::Month::January, 1)
+            .map_err(|err| format!("{}: {err}", year + 1))?;
+
+        Ok(Self {
+            from: DateTime(from.midnight().assume_utc()),
+            to: DateTime(to.midnight().assume_utc()),
+            kwh,
+            cost: None,
+            currency: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn month_boundaries() {
+        let bucket = UsageBucket::month(2023, 12, 1.0).expect("valid month");
+        assert_eq!(bucket.from.0, time::macros::datetime!(2023-12-01 00:00 UTC));
+        assert_eq!(bucket.to.0, time::macros::datetime!(2024-01-01 00:00 UTC));
+
+        UsageBucket::month(2023, 13, 1.0).expect_err("invalid month");
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{BytesBody, Client, Error, JsonBody, Result, unauthenticated_req};
+
+static MIN_PASSWORD_LEN: usize = 8;
+
+fn validate_email(email: &str) -> Result<()> {
+    match email.split_once('@') {
+        Some((user, domain)) if !user.is_empty() && domain.contains('.') => Ok(()),
+        _ => Err(Error::InvalidRequest(format!("invalid email `{email}`"))),
+    }
+}
+
+fn validate_password(password: &str) -> Result<()> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(Error::InvalidRequest(format!(
+            "password must be at least {MIN_PASSWORD_LEN} characters"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Updates the given fields of the current users profile
+#[derive(Default)]
+pub struct UpdateProfile {
+    body: UpdateProfileBody,
+}
+
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateProfileBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_name: Option<String>,
+    #[serde(rename = "eMail", skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[serde(rename = "phoneNo", skip_serializing_if = "Option::is_none")]
+    phone_nr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locale: Option<String>,
+}
+
+impl UpdateProfile {
+    pub fn firstname(mut self, firstname: impl Into<String>) -> Self {
+        self.body.first_name = Some(firstname.into());
+        self
+    }
+
+    pub fn lastname(mut self, lastname: impl Into<String>) -> Self {
+        self.body.last_name = Some(lastname.into());
+        self
+    }
+
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.body.email = Some(email.into());
+        self
+    }
+
+    pub fn phone_nr(mut self, phone_nr: impl Into<String>) -> Self {
+        self.body.phone_nr = Some(phone_nr.into());
+        self
+    }
+
+    /// eg `sv-SE`
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.body.locale = Some(locale.into());
+        self
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        if let Some(email) = &self.body.email {
+            validate_email(email)?;
+        }
+
+        client
+            .req::<_, BytesBody>(
+                http::Method::POST,
+                "api/accounts/profile",
+                JsonBody(&self.body),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePassword {
+    old_password: String,
+    new_password: String,
+}
+
+impl ChangePassword {
+    pub fn new(old_password: impl Into<String>, new_password: impl Into<String>) -> Self {
+        Self {
+            old_password: old_password.into(),
+            new_password: new_password.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        validate_password(&self.new_password)?;
+
+        if self.old_password == self.new_password {
+            return Err(Error::InvalidRequest(
+                "new password must differ from the old one".into(),
+            ));
+        }
+
+        client
+            .req::<_, BytesBody>(
+                http::Method::POST,
+                "api/accounts/change_password",
+                JsonBody(self),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Makes Easee send a password reset link. Does not require a session.
+#[derive(serde::Serialize)]
+pub struct RequestPasswordReset {
+    email: String,
+}
+
+impl RequestPasswordReset {
+    pub fn new(email: impl Into<String>) -> Self {
+        Self {
+            email: email.into(),
+        }
+    }
+
+    pub async fn send(&self) -> Result<()> {
+        validate_email(&self.email)?;
+
+        unauthenticated_req::<_, BytesBody>(
+            http::Method::POST,
+            "api/accounts/forgot_password",
+            JsonBody(self),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Registers a new Easee account. Does not require a session.
+///
+/// Easee sends a verification code to the given email/phone before the account can log in.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterAccount {
+    #[serde(rename = "eMail")]
+    email: String,
+    password: String,
+    first_name: String,
+    last_name: String,
+    #[serde(rename = "phoneNo", skip_serializing_if = "Option::is_none")]
+    phone_nr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locale: Option<String>,
+    accepted_terms: bool,
+}
+
+impl RegisterAccount {
+    pub fn new(
+        email: impl Into<String>,
+        password: impl Into<String>,
+        firstname: impl Into<String>,
+        lastname: impl Into<String>,
+    ) -> Self {
+        Self {
+            email: email.into(),
+            password: password.into(),
+            first_name: firstname.into(),
+            last_name: lastname.into(),
+            phone_nr: None,
+            locale: None,
+            accepted_terms: false,
+        }
+    }
+
+    pub fn phone_nr(mut self, phone_nr: impl Into<String>) -> Self {
+        self.phone_nr = Some(phone_nr.into());
+        self
+    }
+
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Must be set, confirming that the account holder has accepted Easees terms
+    pub fn accept_terms(mut self) -> Self {
+        self.accepted_terms = true;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_email(&self.email)?;
+        validate_password(&self.password)?;
+
+        if self.first_name.trim().is_empty() || self.last_name.trim().is_empty() {
+            return Err(Error::InvalidRequest(
+                "first and last name are required".into(),
+            ));
+        }
+
+        if !self.accepted_terms {
+            return Err(Error::InvalidRequest("terms must be accepted".into()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self) -> Result<()> {
+        self.validate()?;
+
+        unauthenticated_req::<_, BytesBody>(
+            http::Method::POST,
+            "api/accounts/register",
+            JsonBody(self),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Profile;
+
+    #[test]
+    fn validate_register() {
+        RegisterAccount::new("test@example.com", "secret123", "Test", "Testsson")
+            .validate()
+            .expect_err("terms not accepted");
+
+        RegisterAccount::new("test@example.com", "short", "Test", "Testsson")
+            .accept_terms()
+            .validate()
+            .expect_err("password too short");
+
+        RegisterAccount::new("example.com", "secret123", "Test", "Testsson")
+            .accept_terms()
+            .validate()
+            .expect_err("invalid email");
+
+        RegisterAccount::new("test@example.com", "secret123", "Test", "Testsson")
+            .accept_terms()
+            .validate()
+            .expect("valid");
+    }
+
+    #[test]
+    fn deserialize_profile() {
+        let s = r#"
+{
+  "userId": 265514,
+  "eMail": "test@example.com",
+  "phoneNo": "+46700000000",
+  "firstName": "Test",
+  "lastName": "Testsson",
+  "locale": "sv-SE",
+  "isEmailVerified": true,
+  "isPhoneVerified": false,
+  "acceptedTermsVersion": "2023-01",
+  "acceptedTermsAt": "2023-01-12T14:44:57.04698",
+  "createdOn": "2021-02-24T11:02:21.282008"
+}
+"#;
+        let profile = serde_json::from_str::<Profile>(s).expect("deserializing");
+        assert_eq!(profile.locale.as_deref(), Some("sv-SE"));
+        assert_eq!(profile.is_email_verified, Some(true));
+
+        // Older replies only had the basic fields
+        serde_json::from_str::<Profile>(
+            r#"{ "userId": 1, "eMail": "a@b.se", "phoneNo": "", "firstName": "", "lastName": "" }"#,
+        )
+        .expect("deserializing basic profile");
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    AuthorizationToken, BytesBody, Client, Error, JsonBody, NoBody, Result, SiteId,
+    models::strip_tag_prefix,
+};
+
+/// Where a list of authorization tokens is kept
+#[derive(Debug, Clone)]
+pub enum TokenOwner {
+    Charger(String),
+    Site(SiteId),
+}
+
+impl TokenOwner {
+    fn path(&self) -> String {
+        match self {
+            TokenOwner::Charger(charger_id) => format!("api/chargers/{charger_id}/rfid"),
+            TokenOwner::Site(site_id) => format!("api/sites/{site_id}/rfid"),
+        }
+    }
+}
+
+pub struct GetAuthorizationTokens(pub TokenOwner);
+
+impl GetAuthorizationTokens {
+    pub fn charger(charger_id: impl Into<String>) -> Self {
+        Self(TokenOwner::Charger(charger_id.into()))
+    }
+
+    pub fn site(site_id: SiteId) -> Self {
+        Self(TokenOwner::Site(site_id))
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Vec<AuthorizationToken>> {
+        client
+            .req::<_, JsonBody<Vec<AuthorizationToken>>>(http::Method::GET, &self.0.path(), NoBody)
+            .await
+    }
+}
+
+/// Accepts tags as sessions or token lists show them, refusing anything that isn't a plain
+/// alphanumeric tag since it ends up in the url
+fn valid_tag(tag: &str) -> Result<&str> {
+    let tag = strip_tag_prefix(tag);
+
+    if tag.is_empty() || !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(Error::InvalidRequest(format!(
+            "authorization token must be alphanumeric, got `{tag}`"
+        )));
+    }
+
+    Ok(tag)
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenBody<'a> {
+    rfid_tag: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_id: Option<i64>,
+}
+
+/// Registers a new token, or updates the name and user of an existing one
+pub struct AddAuthorizationToken {
+    owner: TokenOwner,
+    tag: String,
+    name: Option<String>,
+    user_id: Option<i64>,
+}
+
+impl AddAuthorizationToken {
+    pub fn new(owner: TokenOwner, tag: impl Into<String>) -> Self {
+        Self {
+            owner,
+            tag: tag.into(),
+            name: None,
+            user_id: None,
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn user_id(mut self, user_id: i64) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let tag = valid_tag(&self.tag)?;
+
+        let body = TokenBody {
+            rfid_tag: tag,
+            name: self.name.as_deref(),
+            user_id: self.user_id,
+        };
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &self.owner.path(), JsonBody(&body))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Renames an already registered token
+pub struct RenameAuthorizationToken {
+    owner: TokenOwner,
+    tag: String,
+    name: String,
+}
+
+impl RenameAuthorizationToken {
+    pub fn new(owner: TokenOwner, tag: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            owner,
+            tag: tag.into(),
+            name: name.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let tag = valid_tag(&self.tag)?;
+
+        let url = format!("{}/{tag}", self.owner.path());
+        let body = TokenBody {
+            rfid_tag: tag,
+            name: Some(&self.name),
+            user_id: None,
+        };
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&body))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Revokes a token so it can no longer be used to charge
+pub struct RemoveAuthorizationToken {
+    owner: TokenOwner,
+    tag: String,
+}
+
+impl RemoveAuthorizationToken {
+    pub fn new(owner: TokenOwner, tag: impl Into<String>) -> Self {
+        Self {
+            owner,
+            tag: tag.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let tag = valid_tag(&self.tag)?;
+        let url = format!("{}/{tag}", self.owner.path());
+
+        client
+            .req::<_, BytesBody>(http::Method::DELETE, &url, NoBody)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::TestServer;
+
+    #[test]
+    fn tags_are_validated() {
+        assert_eq!(valid_tag(" nfc-04A2B3C4D5 ").expect("valid"), "04A2B3C4D5");
+        assert_eq!(valid_tag("04a2b3c4d5").expect("valid"), "04a2b3c4d5");
+
+        for tag in ["", "nfc-", "04a2/../../sites", "04a2?x=1", "04 a2"] {
+            assert!(
+                matches!(valid_tag(tag), Err(Error::InvalidRequest(_))),
+                "{tag}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rename_and_remove() {
+        let server = TestServer::start(vec![(200, ""), (200, "")]);
+        let client = server.client();
+        let owner = || TokenOwner::Charger("EC3VJ7GU".into());
+
+        RenameAuthorizationToken::new(owner(), "nfc-04a2b3c4d5", "Blue tag")
+            .send(&client)
+            .await
+            .expect("rename");
+        RemoveAuthorizationToken::new(owner(), " 04A2B3C4D5 ")
+            .send(&client)
+            .await
+            .expect("remove");
+
+        // Refused before anything is sent
+        let invalid = RemoveAuthorizationToken::new(owner(), "04a2/../../sites")
+            .send(&client)
+            .await;
+        assert!(
+            matches!(invalid, Err(Error::InvalidRequest(_))),
+            "{invalid:?}"
+        );
+
+        let requests = server.requests();
+        let calls = requests
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str(), r.body.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            calls,
+            [
+                (
+                    "POST",
+                    "/api/chargers/EC3VJ7GU/rfid/04a2b3c4d5",
+                    r#"{"rfidTag":"04a2b3c4d5","name":"Blue tag"}"#
+                ),
+                ("DELETE", "/api/chargers/EC3VJ7GU/rfid/04A2B3C4D5", ""),
+            ]
+        );
+    }
+}
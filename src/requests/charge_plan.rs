@@ -0,0 +1,194 @@
+use crate::{BasicChargePlan, BytesBody, Client, JsonBody, NoBody, Result, WeeklyChargePlan};
+
+/// Fetches the chargers basic charge plan. Responds with `Error::NotFound` if none is set.
+pub struct GetBasicChargePlan {
+    charger_id: String,
+}
+
+impl GetBasicChargePlan {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<BasicChargePlan> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/basic_charge_plan");
+
+        client
+            .req::<_, JsonBody<BasicChargePlan>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+pub struct SetBasicChargePlan {
+    charger_id: String,
+    plan: BasicChargePlan,
+}
+
+impl SetBasicChargePlan {
+    pub fn new(charger_id: impl Into<String>, plan: BasicChargePlan) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+            plan,
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/basic_charge_plan");
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&self.plan))
+            .await?;
+
+        Ok(())
+    }
+}
+
+pub struct DeleteBasicChargePlan {
+    charger_id: String,
+}
+
+impl DeleteBasicChargePlan {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/basic_charge_plan");
+
+        client
+            .req::<_, BytesBody>(http::Method::DELETE, &url, NoBody)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Fetches the chargers weekly charge plan. Responds with `Error::NotFound` if none is set.
+pub struct GetWeeklyChargePlan {
+    charger_id: String,
+}
+
+impl GetWeeklyChargePlan {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<WeeklyChargePlan> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/weekly_charge_plan");
+
+        client
+            .req::<_, JsonBody<WeeklyChargePlan>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+pub struct SetWeeklyChargePlan {
+    charger_id: String,
+    plan: WeeklyChargePlan,
+}
+
+impl SetWeeklyChargePlan {
+    pub fn new(charger_id: impl Into<String>, plan: WeeklyChargePlan) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+            plan,
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/weekly_charge_plan");
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&self.plan))
+            .await?;
+
+        Ok(())
+    }
+}
+
+pub struct DeleteWeeklyChargePlan {
+    charger_id: String,
+}
+
+impl DeleteWeeklyChargePlan {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/weekly_charge_plan");
+
+        client
+            .req::<_, BytesBody>(http::Method::DELETE, &url, NoBody)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, test_server::TestServer};
+
+    const PLAN: &str = r#"{"id":"EC3VJ7GU","chargeStartTime":"2023-08-20T22:00:00Z","chargeStopTime":"2023-08-21T06:00:00Z","repeat":true,"isEnabled":true,"timeZone":null,"chargingCurrentLimit":null}"#;
+
+    #[tokio::test]
+    async fn get_set_delete() {
+        let server = TestServer::start(vec![(200, PLAN), (200, ""), (200, ""), (404, "")]);
+        let client = server.client();
+
+        let plan = GetBasicChargePlan::new("EC3VJ7GU")
+            .send(&client)
+            .await
+            .expect("get");
+        assert_eq!(plan.id.as_deref(), Some("EC3VJ7GU"));
+
+        SetBasicChargePlan::new("EC3VJ7GU", plan)
+            .send(&client)
+            .await
+            .expect("set");
+        DeleteBasicChargePlan::new("EC3VJ7GU")
+            .send(&client)
+            .await
+            .expect("delete");
+
+        let missing = GetWeeklyChargePlan::new("EC3VJ7GU").send(&client).await;
+        assert!(matches!(missing, Err(Error::NotFound)), "{missing:?}");
+
+        let requests = server.requests();
+        let calls = requests
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            calls,
+            [
+                ("GET", "/api/chargers/EC3VJ7GU/basic_charge_plan"),
+                ("POST", "/api/chargers/EC3VJ7GU/basic_charge_plan"),
+                ("DELETE", "/api/chargers/EC3VJ7GU/basic_charge_plan"),
+                ("GET", "/api/chargers/EC3VJ7GU/weekly_charge_plan"),
+            ]
+        );
+
+        // The plan is sent back exactly as it was received
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&requests[1].body).expect("body"),
+            serde_json::from_str::<serde_json::Value>(PLAN).expect("plan")
+        );
+    }
+}
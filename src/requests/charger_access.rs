@@ -0,0 +1,75 @@
+use crate::{AccessLevel, BytesBody, Client, Error, JsonBody, NoBody, Result};
+
+pub struct GetChargerAccess {
+    charger_id: String,
+}
+
+impl GetChargerAccess {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<AccessLevel> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/access");
+
+        client
+            .req::<_, JsonBody<AccessLevel>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+pub struct SetChargerAccess {
+    charger_id: String,
+    level: AccessLevel,
+}
+
+impl SetChargerAccess {
+    pub fn new(charger_id: impl Into<String>, level: AccessLevel) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+            level,
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        if let AccessLevel::Unknown(n) = self.level {
+            return Err(Error::InvalidRequest(format!("unknown access level {n}")));
+        }
+
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/access");
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(self.level))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Removes the chargers own access level, making it inherit the level of its site
+pub struct ResetChargerAccess {
+    charger_id: String,
+}
+
+impl ResetChargerAccess {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/access");
+
+        client
+            .req::<_, BytesBody>(http::Method::DELETE, &url, NoBody)
+            .await?;
+
+        Ok(())
+    }
+}
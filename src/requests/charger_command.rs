@@ -0,0 +1,72 @@
+use crate::{Client, CommandResponse, JsonBody, NoBody, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargerCommand {
+    StartCharging,
+    StopCharging,
+    PauseCharging,
+    ResumeCharging,
+    ToggleCharging,
+    Reboot,
+    UpdateFirmware,
+}
+
+impl ChargerCommand {
+    pub const ALL: [ChargerCommand; 7] = [
+        ChargerCommand::StartCharging,
+        ChargerCommand::StopCharging,
+        ChargerCommand::PauseCharging,
+        ChargerCommand::ResumeCharging,
+        ChargerCommand::ToggleCharging,
+        ChargerCommand::Reboot,
+        ChargerCommand::UpdateFirmware,
+    ];
+
+    /// The command as named in the api, eg `start_charging`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChargerCommand::StartCharging => "start_charging",
+            ChargerCommand::StopCharging => "stop_charging",
+            ChargerCommand::PauseCharging => "pause_charging",
+            ChargerCommand::ResumeCharging => "resume_charging",
+            ChargerCommand::ToggleCharging => "toggle_charging",
+            ChargerCommand::Reboot => "reboot",
+            ChargerCommand::UpdateFirmware => "update_firmware",
+        }
+    }
+}
+
+impl std::str::FromStr for ChargerCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|command| command.name() == s)
+            .ok_or_else(|| format!("unknown charger command `{s}`"))
+    }
+}
+
+pub struct SendChargerCommand {
+    charger_id: String,
+    command: ChargerCommand,
+}
+
+impl SendChargerCommand {
+    pub fn new(charger_id: impl Into<String>, command: ChargerCommand) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+            command,
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<CommandResponse> {
+        let charger_id = &self.charger_id;
+        let command = self.command.name();
+        let url = format!("api/chargers/{charger_id}/commands/{command}");
+
+        client
+            .req::<_, JsonBody<CommandResponse>>(http::Method::POST, &url, NoBody)
+            .await
+    }
+}
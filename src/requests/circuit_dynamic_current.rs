@@ -0,0 +1,146 @@
+use crate::{BytesBody, CircuitId, Client, Error, JsonBody, NoBody, Result, SiteId};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitDynamicCurrent {
+    pub phase1: f64,
+    pub phase2: f64,
+    pub phase3: f64,
+}
+
+pub struct GetCircuitDynamicCurrent {
+    site_id: SiteId,
+    circuit_id: CircuitId,
+}
+
+impl GetCircuitDynamicCurrent {
+    pub fn new(site_id: SiteId, circuit_id: CircuitId) -> Self {
+        Self {
+            site_id,
+            circuit_id,
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<CircuitDynamicCurrent> {
+        let site_id = self.site_id;
+        let circuit_id = self.circuit_id;
+        let url = format!("api/sites/{site_id}/circuits/{circuit_id}/dynamicCurrent");
+
+        client
+            .req::<_, JsonBody<CircuitDynamicCurrent>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+pub struct SetCircuitDynamicCurrent {
+    site_id: SiteId,
+    circuit_id: CircuitId,
+    phases: CircuitDynamicCurrent,
+    time_to_live: Option<time::Duration>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetCircuitDynamicCurrentBody<'a> {
+    #[serde(flatten)]
+    phases: &'a CircuitDynamicCurrent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_to_live: Option<i64>,
+}
+
+impl SetCircuitDynamicCurrent {
+    pub fn new(site_id: SiteId, circuit_id: CircuitId, phases: CircuitDynamicCurrent) -> Self {
+        Self {
+            site_id,
+            circuit_id,
+            phases,
+            time_to_live: None,
+        }
+    }
+
+    /// Sets the same limit on all three phases
+    pub fn all_phases(site_id: SiteId, circuit_id: CircuitId, amps: f64) -> Self {
+        Self::new(
+            site_id,
+            circuit_id,
+            CircuitDynamicCurrent {
+                phase1: amps,
+                phase2: amps,
+                phase3: amps,
+            },
+        )
+    }
+
+    /// How long the limit is kept before the circuit falls back to its default.
+    /// Easee counts in whole minutes, so the duration is rounded up.
+    pub fn time_to_live(mut self, ttl: time::Duration) -> Self {
+        self.time_to_live = Some(ttl);
+        self
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let CircuitDynamicCurrent {
+            phase1,
+            phase2,
+            phase3,
+        } = self.phases;
+
+        if [phase1, phase2, phase3]
+            .iter()
+            .any(|amps| !amps.is_finite() || *amps < 0.0)
+        {
+            return Err(Error::InvalidRequest(format!(
+                "dynamic circuit current must be positive, got {phase1}/{phase2}/{phase3}A"
+            )));
+        }
+
+        let site_id = self.site_id;
+        let circuit_id = self.circuit_id;
+        let url = format!("api/sites/{site_id}/circuits/{circuit_id}/dynamicCurrent");
+
+        let body = SetCircuitDynamicCurrentBody {
+            phases: &self.phases,
+            time_to_live: self.time_to_live.map(ttl_minutes),
+        };
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&body))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Easee takes time to live as whole minutes, round up so we never shorten it.
+pub(crate) fn ttl_minutes(ttl: time::Duration) -> i64 {
+    let minutes = ttl.whole_minutes();
+    if ttl > time::Duration::minutes(minutes) {
+        minutes + 1
+    } else {
+        minutes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_body() {
+        let phases = CircuitDynamicCurrent {
+            phase1: 10.0,
+            phase2: 11.0,
+            phase3: 12.0,
+        };
+
+        let body = SetCircuitDynamicCurrentBody {
+            phases: &phases,
+            time_to_live: Some(ttl_minutes(time::Duration::seconds(90))),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&body).expect("serializing"),
+            r#"{"phase1":10.0,"phase2":11.0,"phase3":12.0,"timeToLive":2}"#
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use super::circuit_dynamic_current::{
+    CircuitDynamicCurrent, SetCircuitDynamicCurrent, ttl_minutes,
+};
+use crate::{
+    ChargerConfig, Circuit, Client, CommandResponse, Error, JsonBody, Result, Site,
+    requests::GetChargerConfig, requests::GetChargerState,
+};
+
+/// The dynamic current limits currently applied to a charger.
+///
+/// The per phase values are the dynamic limits of the circuit the charger is connected to,
+/// and are only reported by chargers that support it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicChargerCurrent {
+    pub amps: Option<f64>,
+    pub circuit_p1: Option<f64>,
+    pub circuit_p2: Option<f64>,
+    pub circuit_p3: Option<f64>,
+}
+
+impl DynamicChargerCurrent {
+    /// The per phase limits, if the charger reported all three
+    pub fn phases(&self) -> Option<CircuitDynamicCurrent> {
+        Some(CircuitDynamicCurrent {
+            phase1: self.circuit_p1?,
+            phase2: self.circuit_p2?,
+            phase3: self.circuit_p3?,
+        })
+    }
+}
+
+pub struct GetDynamicChargerCurrent {
+    charger_id: String,
+}
+
+impl GetDynamicChargerCurrent {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<DynamicChargerCurrent> {
+        let state = GetChargerState::new(&self.charger_id).send(client).await?;
+
+        Ok(DynamicChargerCurrent {
+            amps: state.dynamic_charger_current,
+            circuit_p1: state.dynamic_circuit_current_p1,
+            circuit_p2: state.dynamic_circuit_current_p2,
+            circuit_p3: state.dynamic_circuit_current_p3,
+        })
+    }
+}
+
+enum Limit {
+    Charger(f64),
+    Phases(CircuitDynamicCurrent),
+}
+
+pub struct SetDynamicChargerCurrent {
+    charger_id: String,
+    limit: Limit,
+    time_to_live: Option<time::Duration>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetDynamicChargerCurrentBody {
+    amps: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_to_live: Option<i64>,
+}
+
+impl SetDynamicChargerCurrent {
+    /// Temporarily limits the charger to `amps`. Setting it to 0 pauses charging.
+    pub fn new(charger_id: impl Into<String>, amps: f64) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+            limit: Limit::Charger(amps),
+            time_to_live: None,
+        }
+    }
+
+    /// Temporarily limits each phase. Easee only takes per phase limits for a whole circuit, so
+    /// they are set on the circuit the charger is connected to and apply to every charger on it.
+    pub fn per_phase(charger_id: impl Into<String>, phases: CircuitDynamicCurrent) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+            limit: Limit::Phases(phases),
+            time_to_live: None,
+        }
+    }
+
+    /// How long the limit is kept before the charger falls back to its default.
+    /// Easee counts in whole minutes, so the duration is rounded up.
+    pub fn time_to_live(mut self, ttl: time::Duration) -> Self {
+        self.time_to_live = Some(ttl);
+        self
+    }
+
+    /// Checks the requested current against the chargers configured max and the rated current
+    /// of the circuit it is connected to in `site`.
+    pub fn validate(&self, site: &Site, config: &ChargerConfig) -> Result<()> {
+        self.validate_site(site)?;
+        self.validate_config(config)
+    }
+
+    /// Checks everything that doesn't need the chargers config, returning its circuit
+    fn validate_site<'a>(&self, site: &'a Site) -> Result<&'a Circuit> {
+        let charger_id = &self.charger_id;
+        let amps = match &self.limit {
+            Limit::Charger(amps) => vec![*amps],
+            Limit::Phases(phases) => vec![phases.phase1, phases.phase2, phases.phase3],
+        };
+
+        if let Some(amps) = amps.iter().find(|amps| !amps.is_finite() || **amps < 0.0) {
+            return Err(Error::InvalidRequest(format!(
+                "dynamic current must be a positive number of amps, got {amps}"
+            )));
+        }
+
+        if let Some(ttl) = self.time_to_live
+            && ttl.is_negative()
+        {
+            return Err(Error::InvalidRequest(format!(
+                "time to live must be positive, got {ttl}"
+            )));
+        }
+
+        let circuit = site.circuit_for_charger(charger_id).ok_or_else(|| {
+            Error::InvalidRequest(format!(
+                "charger {charger_id} is not part of site {}",
+                site.id
+            ))
+        })?;
+
+        if let Some(amps) = amps.iter().find(|amps| **amps > circuit.rated_current) {
+            return Err(Error::InvalidRequest(format!(
+                "{amps}A exceeds the rated current {}A of circuit {}",
+                circuit.rated_current, circuit.id
+            )));
+        }
+
+        Ok(circuit)
+    }
+
+    /// The configured max only limits a single charger, and 0 never exceeds it
+    fn needs_config(&self) -> bool {
+        matches!(self.limit, Limit::Charger(amps) if amps > 0.0)
+    }
+
+    fn validate_config(&self, config: &ChargerConfig) -> Result<()> {
+        if let Limit::Charger(amps) = self.limit
+            && let Some(max) = config.max_charger_current
+            && amps > max
+        {
+            return Err(Error::InvalidRequest(format!(
+                "{amps}A exceeds the configured max current {max}A of charger {}",
+                self.charger_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Validates the request against `site`, and the chargers current config when its max
+    /// applies, before sending it. Returns the command sent to the charger, per phase limits
+    /// are set on the circuit without one.
+    pub async fn send(&self, client: &Client, site: &Site) -> Result<Option<CommandResponse>> {
+        let circuit = self.validate_site(site)?;
+
+        if self.needs_config() {
+            let config = GetChargerConfig::new(&self.charger_id).send(client).await?;
+            self.validate_config(&config)?;
+        }
+
+        let amps = match &self.limit {
+            Limit::Charger(amps) => *amps,
+            Limit::Phases(phases) => {
+                let mut req = SetCircuitDynamicCurrent::new(site.id, circuit.id, phases.clone());
+                if let Some(ttl) = self.time_to_live {
+                    req = req.time_to_live(ttl);
+                }
+                req.send(client).await?;
+
+                return Ok(None);
+            }
+        };
+
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/commands/set_dynamic_charger_current");
+
+        let body = SetDynamicChargerCurrentBody {
+            amps,
+            time_to_live: self.time_to_live.map(ttl_minutes),
+        };
+
+        client
+            .req::<_, JsonBody<CommandResponse>>(http::Method::POST, &url, JsonBody(&body))
+            .await
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::TestServer;
+
+    const COMMAND: &str = r#"{"device":"EC000001","commandId":42,"ticks":1}"#;
+
+    fn site() -> Site {
+        let s = r#"
+{
+  "id": 1,
+  "siteKey": "AAAA-1111",
+  "name": "Test",
+  "levelOfAccess": null,
+  "address": { "street": null, "buildingNumber": null, "zip": null, "area": null, "country": null, "latitude": null, "longitude": null, "altitude": null },
+  "contactInfo": null,
+  "costPerKWh": null,
+  "costPerKwhExcludeVat": null,
+  "currencyId": null,
+  "siteType": 100,
+  "ratedCurrent": 63.0,
+  "vat": null,
+  "partnerId": null,
+  "installerId": null,
+  "useDynamicMaster": false,
+  "circuits": [
+    {
+      "id": 10,
+      "siteId": 1,
+      "circuitPanelId": 1,
+      "panelName": "1",
+      "ratedCurrent": 20.0,
+      "chargers": [
+        {
+          "id": "EC000001",
+          "name": "Charger",
+          "color": null,
+          "createdOn": "2022-10-12T05:01:39.518305",
+          "updatedOn": "2023-01-12T14:58:32.612267",
+          "backPlate": { "id": "1", "masterBackPlateId": "1", "name": "", "features": [] },
+          "levelOfAccess": null,
+          "productCode": 100,
+          "userRole": null,
+          "isTemporary": false
+        }
+      ],
+      "masterBackplate": null,
+      "useDynamicMaster": false,
+      "parentCircuitId": null
+    }
+  ],
+  "equalizers": [],
+  "createdOn": "2023-01-12T14:44:57.04698",
+  "updatedOn": "2023-03-30T08:17:59.292387",
+  "userRole": null,
+  "allowedSiteActions": []
+}
+"#;
+        serde_json::from_str(s).expect("deserializing site")
+    }
+
+    fn config(max: f64) -> ChargerConfig {
+        ChargerConfig {
+            is_enabled: Some(true),
+            authorization_required: None,
+            max_charger_current: Some(max),
+            phase_mode: None,
+            offline_charging_mode: None,
+            wi_fi_ssid: None,
+        }
+    }
+
+    #[test]
+    fn validate() {
+        let site = site();
+
+        SetDynamicChargerCurrent::new("EC000001", 16.0)
+            .validate(&site, &config(32.0))
+            .expect("16A should be allowed");
+
+        // Circuit is rated for 20A
+        SetDynamicChargerCurrent::new("EC000001", 25.0)
+            .validate(&site, &config(32.0))
+            .expect_err("exceeds circuit");
+
+        // Charger is configured for 10A
+        SetDynamicChargerCurrent::new("EC000001", 16.0)
+            .validate(&site, &config(10.0))
+            .expect_err("exceeds charger max");
+
+        SetDynamicChargerCurrent::new("EC999999", 6.0)
+            .validate(&site, &config(32.0))
+            .expect_err("charger not in site");
+
+        SetDynamicChargerCurrent::new("EC000001", -1.0)
+            .validate(&site, &config(32.0))
+            .expect_err("negative amps");
+
+        SetDynamicChargerCurrent::per_phase(
+            "EC000001",
+            CircuitDynamicCurrent {
+                phase1: 16.0,
+                phase2: 25.0,
+                phase3: 16.0,
+            },
+        )
+        .validate(&site, &config(10.0))
+        .expect_err("second phase exceeds circuit");
+    }
+
+    #[tokio::test]
+    async fn send() {
+        let server = TestServer::start(vec![
+            (200, r#"{"maxChargerCurrent":32.0}"#),
+            (200, COMMAND),
+            (200, COMMAND),
+            (200, ""),
+        ]);
+        let client = server.client();
+        let site = site();
+
+        let res = SetDynamicChargerCurrent::new("EC000001", 16.0)
+            .time_to_live(time::Duration::minutes(10))
+            .send(&client, &site)
+            .await
+            .expect("limit");
+        assert_eq!(res.map(|r| r.command_id), Some(42));
+
+        // Pausing can't exceed the configured max
+        SetDynamicChargerCurrent::new("EC000001", 0.0)
+            .send(&client, &site)
+            .await
+            .expect("pause");
+
+        let res = SetDynamicChargerCurrent::per_phase(
+            "EC000001",
+            CircuitDynamicCurrent {
+                phase1: 16.0,
+                phase2: 10.0,
+                phase3: 0.0,
+            },
+        )
+        .send(&client, &site)
+        .await
+        .expect("per phase");
+        assert_eq!(res, None);
+
+        let requests = server.requests();
+        let calls = requests
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str(), r.body.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            calls,
+            [
+                ("GET", "/api/chargers/EC000001/config", ""),
+                (
+                    "POST",
+                    "/api/chargers/EC000001/commands/set_dynamic_charger_current",
+                    r#"{"amps":16.0,"timeToLive":10}"#
+                ),
+                (
+                    "POST",
+                    "/api/chargers/EC000001/commands/set_dynamic_charger_current",
+                    r#"{"amps":0.0}"#
+                ),
+                (
+                    "POST",
+                    "/api/sites/1/circuits/10/dynamicCurrent",
+                    r#"{"phase1":16.0,"phase2":10.0,"phase3":0.0}"#
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    BytesBody, Client, CommandResponse, Equalizer, EqualizerConfig, EqualizerState, Error,
+    JsonBody, NoBody, Result,
+};
+
+pub struct GetEqualizer {
+    equalizer_id: String,
+}
+
+impl GetEqualizer {
+    pub fn new(equalizer_id: impl Into<String>) -> Self {
+        Self {
+            equalizer_id: equalizer_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Equalizer> {
+        let equalizer_id = &self.equalizer_id;
+        let url = format!("api/equalizers/{equalizer_id}");
+
+        client
+            .req::<_, JsonBody<Equalizer>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+pub struct GetEqualizerState {
+    equalizer_id: String,
+}
+
+impl GetEqualizerState {
+    pub fn new(equalizer_id: impl Into<String>) -> Self {
+        Self {
+            equalizer_id: equalizer_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<EqualizerState> {
+        let equalizer_id = &self.equalizer_id;
+        let url = format!("api/equalizers/{equalizer_id}/state");
+
+        client
+            .req::<_, JsonBody<EqualizerState>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+pub struct GetEqualizerConfig {
+    equalizer_id: String,
+}
+
+impl GetEqualizerConfig {
+    pub fn new(equalizer_id: impl Into<String>) -> Self {
+        Self {
+            equalizer_id: equalizer_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<EqualizerConfig> {
+        let equalizer_id = &self.equalizer_id;
+        let url = format!("api/equalizers/{equalizer_id}/config");
+
+        client
+            .req::<_, JsonBody<EqualizerConfig>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+/// Updates the given parts of the equalizer config, leaving the rest as is
+pub struct UpdateEqualizerConfig {
+    equalizer_id: String,
+    body: UpdateEqualizerConfigBody,
+}
+
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateEqualizerConfigBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    main_fuse: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    surplus_charging: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_allocated_current: Option<f64>,
+}
+
+impl UpdateEqualizerConfig {
+    pub fn new(equalizer_id: impl Into<String>) -> Self {
+        Self {
+            equalizer_id: equalizer_id.into(),
+            body: UpdateEqualizerConfigBody::default(),
+        }
+    }
+
+    pub fn main_fuse(mut self, amps: f64) -> Self {
+        self.body.main_fuse = Some(amps);
+        self
+    }
+
+    pub fn surplus_charging(mut self, enabled: bool) -> Self {
+        self.body.surplus_charging = Some(enabled);
+        self
+    }
+
+    pub fn max_allocated_current(mut self, amps: f64) -> Self {
+        self.body.max_allocated_current = Some(amps);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        let UpdateEqualizerConfigBody {
+            main_fuse,
+            surplus_charging,
+            max_allocated_current,
+        } = self.body;
+
+        if main_fuse.is_none() && surplus_charging.is_none() && max_allocated_current.is_none() {
+            return Err(Error::InvalidRequest(
+                "no equalizer settings to update".into(),
+            ));
+        }
+
+        for amps in [main_fuse, max_allocated_current].into_iter().flatten() {
+            if !amps.is_finite() || amps < 0.0 {
+                return Err(Error::InvalidRequest(format!(
+                    "equalizer current must be positive, got {amps}A"
+                )));
+            }
+        }
+
+        if let (Some(fuse), Some(max)) = (main_fuse, max_allocated_current)
+            && max > fuse
+        {
+            return Err(Error::InvalidRequest(format!(
+                "max allocated current {max}A exceeds the main fuse {fuse}A"
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        self.validate()?;
+
+        let equalizer_id = &self.equalizer_id;
+        let url = format!("api/equalizers/{equalizer_id}/settings");
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&self.body))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EqualizerCommand {
+    Reboot,
+    UpdateFirmware,
+}
+
+impl EqualizerCommand {
+    fn name(&self) -> &'static str {
+        match self {
+            EqualizerCommand::Reboot => "reboot",
+            EqualizerCommand::UpdateFirmware => "update_firmware",
+        }
+    }
+}
+
+pub struct SendEqualizerCommand {
+    equalizer_id: String,
+    command: EqualizerCommand,
+}
+
+impl SendEqualizerCommand {
+    pub fn new(equalizer_id: impl Into<String>, command: EqualizerCommand) -> Self {
+        Self {
+            equalizer_id: equalizer_id.into(),
+            command,
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<CommandResponse> {
+        let equalizer_id = &self.equalizer_id;
+        let command = self.command.name();
+        let url = format!("api/equalizers/{equalizer_id}/commands/{command}");
+
+        client
+            .req::<_, JsonBody<CommandResponse>>(http::Method::POST, &url, NoBody)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_config() {
+        UpdateEqualizerConfig::new("QPTJH7UN")
+            .validate()
+            .expect_err("nothing to update");
+
+        UpdateEqualizerConfig::new("QPTJH7UN")
+            .main_fuse(25.0)
+            .max_allocated_current(20.0)
+            .surplus_charging(true)
+            .validate()
+            .expect("valid");
+
+        UpdateEqualizerConfig::new("QPTJH7UN")
+            .main_fuse(25.0)
+            .max_allocated_current(32.0)
+            .validate()
+            .expect_err("exceeds main fuse");
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{ChargerConfig, ChargerState, Client, JsonBody, NoBody, Result};
+
+pub struct GetChargerState {
+    charger_id: String,
+}
+
+impl GetChargerState {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<ChargerState> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/state");
+
+        client
+            .req::<_, JsonBody<ChargerState>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+pub struct GetChargerConfig {
+    charger_id: String,
+}
+
+impl GetChargerConfig {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<ChargerConfig> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/{charger_id}/config");
+
+        client
+            .req::<_, JsonBody<ChargerConfig>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
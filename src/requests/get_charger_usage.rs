@@ -0,0 +1,208 @@
+use crate::{Client, DateTime, Error, JsonBody, NoBody, Result, UsageBucket};
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawHourlyUsage {
+    from: DateTime,
+    to: Option<DateTime>,
+    total_energy: f64,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPeriodUsage {
+    year: i32,
+    month: Option<u8>,
+    total_energy_usage: f64,
+    total_cost: Option<f64>,
+    currency_id: Option<String>,
+}
+
+impl RawPeriodUsage {
+    fn into_bucket(self) -> Result<UsageBucket> {
+        let bucket = match self.month {
+            Some(month) => UsageBucket::month(self.year, month, self.total_energy_usage),
+            None => UsageBucket::year(self.year, self.total_energy_usage),
+        };
+
+        let mut bucket = bucket
+            .map_err(|err| Error::InvalidResponse(format!("usage bucket out of range: {err}")))?;
+
+        bucket.cost = self.total_cost;
+        bucket.currency = self.currency_id;
+        Ok(bucket)
+    }
+}
+
+/// Energy used per hour
+pub struct GetChargerHourlyUsage {
+    charger_id: String,
+}
+
+impl GetChargerHourlyUsage {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Vec<UsageBucket>> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/sessions/charger/{charger_id}/hourly");
+
+        let raw = client
+            .req::<_, JsonBody<Vec<RawHourlyUsage>>>(http::Method::GET, &url, NoBody)
+            .await?;
+
+        Ok(raw
+            .into_iter()
+            .map(|hour| UsageBucket {
+                from: hour.from,
+                to: hour
+                    .to
+                    .unwrap_or(DateTime(hour.from.0 + time::Duration::HOUR)),
+                kwh: hour.total_energy,
+                cost: None,
+                currency: None,
+            })
+            .collect())
+    }
+}
+
+/// Energy used and its cost per calendar month (UTC)
+pub struct GetChargerMonthlyUsage {
+    charger_id: String,
+}
+
+impl GetChargerMonthlyUsage {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Vec<UsageBucket>> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/sessions/charger/{charger_id}/monthly");
+
+        client
+            .req::<_, JsonBody<Vec<RawPeriodUsage>>>(http::Method::GET, &url, NoBody)
+            .await?
+            .into_iter()
+            .map(RawPeriodUsage::into_bucket)
+            .collect()
+    }
+}
+
+/// Energy used and its cost per calendar year (UTC)
+pub struct GetChargerYearlyUsage {
+    charger_id: String,
+}
+
+impl GetChargerYearlyUsage {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Vec<UsageBucket>> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/sessions/charger/{charger_id}/yearly");
+
+        client
+            .req::<_, JsonBody<Vec<RawPeriodUsage>>>(http::Method::GET, &url, NoBody)
+            .await?
+            .into_iter()
+            .map(|mut year| {
+                year.month = None;
+                year.into_bucket()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeEnergy {
+    /// kWh
+    pub lifetime_energy: f64,
+    pub timestamp: Option<DateTime>,
+}
+
+/// Total energy the charger has delivered over its lifetime
+pub struct GetChargerLifetimeEnergy {
+    charger_id: String,
+}
+
+impl GetChargerLifetimeEnergy {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<LifetimeEnergy> {
+        let charger_id = &self.charger_id;
+        let url = format!("api/chargers/lifetime-energy/{charger_id}");
+
+        client
+            .req::<_, JsonBody<LifetimeEnergy>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_monthly() {
+        let s = r#"
+[
+  { "year": 2023, "month": 7, "totalEnergyUsage": 123.4, "totalCost": 270.2, "currencyId": "SEK" },
+  { "year": 2023, "month": 8, "totalEnergyUsage": 88.0, "totalCost": null, "currencyId": null }
+]
+"#;
+
+        let buckets = serde_json::from_str::<Vec<RawPeriodUsage>>(s)
+            .expect("deserializing")
+            .into_iter()
+            .map(RawPeriodUsage::into_bucket)
+            .collect::<Result<Vec<_>>>()
+            .expect("converting");
+
+        assert_eq!(buckets[0].kwh, 123.4);
+        assert_eq!(buckets[0].currency.as_deref(), Some("SEK"));
+        assert_eq!(buckets[0].to, buckets[1].from);
+    }
+
+    #[test]
+    fn deserialize_hourly() {
+        let s = r#"
+[
+  { "from": "2023-08-20T12:00:00Z", "to": "2023-08-20T13:00:00Z", "totalEnergy": 1.2 },
+  { "from": "2023-08-20T13:00:00Z", "to": null, "totalEnergy": 0.8 }
+]
+"#;
+
+        let hours = serde_json::from_str::<Vec<RawHourlyUsage>>(s).expect("deserializing");
+
+        assert_eq!(
+            hours[0].from.0,
+            time::macros::datetime!(2023-08-20 12:00 UTC)
+        );
+        assert_eq!(
+            hours[0].to.map(|to| to.0),
+            Some(time::macros::datetime!(2023-08-20 13:00 UTC))
+        );
+        assert_eq!(hours[0].total_energy, 1.2);
+
+        assert_eq!(
+            hours[1].from.0,
+            time::macros::datetime!(2023-08-20 13:00 UTC)
+        );
+        assert_eq!(hours[1].to, None);
+        assert_eq!(hours[1].total_energy, 0.8);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    Client, Result,
+    requests::{GetChargerState, GetEqualizerState},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Device {
+    Charger(String),
+    Equalizer(String),
+}
+
+impl Device {
+    pub fn id(&self) -> &str {
+        match self {
+            Device::Charger(id) | Device::Equalizer(id) => id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirmwareInfo {
+    pub device: Device,
+    pub current: Option<i32>,
+    pub latest: Option<i32>,
+}
+
+impl FirmwareInfo {
+    pub fn update_available(&self) -> bool {
+        match (self.current, self.latest) {
+            (Some(current), Some(latest)) => current < latest,
+            _ => false,
+        }
+    }
+}
+
+/// Fetches the running and latest available firmware of a charger or equalizer
+pub struct GetFirmware(pub Device);
+
+impl GetFirmware {
+    pub fn charger(charger_id: impl Into<String>) -> Self {
+        Self(Device::Charger(charger_id.into()))
+    }
+
+    pub fn equalizer(equalizer_id: impl Into<String>) -> Self {
+        Self(Device::Equalizer(equalizer_id.into()))
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<FirmwareInfo> {
+        let (current, latest) = match &self.0 {
+            Device::Charger(id) => {
+                let state = GetChargerState::new(id).send(client).await?;
+                (state.charger_firmware, state.latest_firmware)
+            }
+            Device::Equalizer(id) => {
+                let state = GetEqualizerState::new(id).send(client).await?;
+                (state.software_release, state.latest_firmware)
+            }
+        };
+
+        Ok(FirmwareInfo {
+            device: self.0.clone(),
+            current,
+            latest,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_available() {
+        let info = |current, latest| FirmwareInfo {
+            device: Device::Charger("EC3VJ7GU".into()),
+            current,
+            latest,
+        };
+
+        assert!(info(Some(300), Some(302)).update_available());
+        assert!(!info(Some(302), Some(302)).update_available());
+        assert!(!info(None, Some(302)).update_available());
+    }
+}
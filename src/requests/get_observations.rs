@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    Client, DateTime, Error, JsonBody, NoBody, ObservationId, ObservationPoint, ObservationValue,
+    Result,
+};
+
+/// Time series per requested observation, ordered by timestamp
+pub type Observations = BTreeMap<ObservationId, Vec<ObservationPoint>>;
+
+/// Fetches historic observations for a charger between `[from - to]`.
+///
+/// Long ranges are split into chunks of at most `max_span` (default 7 days),
+/// which are fetched one after the other and merged.
+pub struct GetObservations {
+    charger_id: String,
+    ids: Vec<ObservationId>,
+    from: DateTime,
+    to: DateTime,
+    max_span: time::Duration,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawObservation {
+    #[serde(alias = "id")]
+    observation_id: ObservationId,
+    timestamp: DateTime,
+    value: ObservationValue,
+}
+
+impl GetObservations {
+    pub fn new(
+        charger_id: impl Into<String>,
+        ids: impl IntoIterator<Item = ObservationId>,
+        from: DateTime,
+        to: DateTime,
+    ) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+            ids: ids.into_iter().collect(),
+            from,
+            to,
+            max_span: time::Duration::days(7),
+        }
+    }
+
+    pub fn max_span(mut self, max_span: time::Duration) -> Self {
+        self.max_span = max_span;
+        self
+    }
+
+    fn chunks(&self) -> Result<Vec<(DateTime, DateTime)>> {
+        if self.ids.is_empty() {
+            return Err(Error::InvalidRequest("no observation ids given".into()));
+        }
+
+        if self.from.0 >= self.to.0 {
+            return Err(Error::InvalidRequest(format!(
+                "from {} must be before to {}",
+                self.from, self.to
+            )));
+        }
+
+        if !self.max_span.is_positive() {
+            return Err(Error::InvalidRequest(format!(
+                "max span must be positive, got {}",
+                self.max_span
+            )));
+        }
+
+        let mut chunks = Vec::new();
+        let mut start = self.from.0;
+
+        while start < self.to.0 {
+            let end = (start + self.max_span).min(self.to.0);
+            chunks.push((DateTime(start), DateTime(end)));
+            start = end;
+        }
+
+        Ok(chunks)
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Observations> {
+        let chunks = self.chunks()?;
+
+        let charger_id = &self.charger_id;
+        let ids = self
+            .ids
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut res = self
+            .ids
+            .iter()
+            .map(|id| (*id, Vec::new()))
+            .collect::<Observations>();
+
+        for (from, to) in chunks {
+            let from_s = from.format_zulu();
+            let to_s = to.format_zulu();
+            let url = format!("api/chargers/{charger_id}/observations/{ids}/{from_s}/{to_s}");
+
+            let raw = client
+                .req::<_, JsonBody<Vec<RawObservation>>>(http::Method::GET, &url, NoBody)
+                .await?;
+
+            for obs in raw {
+                res.entry(obs.observation_id)
+                    .or_default()
+                    .push(ObservationPoint {
+                        timestamp: obs.timestamp,
+                        value: obs.value,
+                    });
+            }
+        }
+
+        // Chunks share their boundaries, so the same point may have been received twice.
+        for points in res.values_mut() {
+            points.sort_by_key(|p| p.timestamp.0);
+            points.dedup_by_key(|p| p.timestamp.0);
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks() {
+        let from = DateTime(time::macros::datetime!(2023-08-01 00:00 UTC));
+        let to = DateTime(time::macros::datetime!(2023-08-20 12:00 UTC));
+
+        let chunks = GetObservations::new("EC3VJ7GU", [ObservationId::TotalPower], from, to)
+            .chunks()
+            .expect("chunking");
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].0, from);
+        assert_eq!(chunks[1].0.0, time::macros::datetime!(2023-08-08 00:00 UTC));
+        assert_eq!(chunks[2].1, to);
+
+        GetObservations::new("EC3VJ7GU", [], from, to)
+            .chunks()
+            .expect_err("no ids");
+
+        GetObservations::new("EC3VJ7GU", [ObservationId::TotalPower], to, from)
+            .chunks()
+            .expect_err("inverted range");
+    }
+
+    #[test]
+    fn deserialize() {
+        let s = r#"
+[
+  { "observationId": 120, "timestamp": "2023-08-20T12:00:00Z", "value": 3.68 },
+  { "observationId": 121, "timestamp": "2023-08-20T12:00:00Z", "value": 12 },
+  { "observationId": 999, "timestamp": "2023-08-20T12:00:00Z", "value": "hello" }
+]
+"#;
+
+        let raw = serde_json::from_str::<Vec<RawObservation>>(s).expect("deserializing");
+        assert_eq!(raw[0].observation_id, ObservationId::TotalPower);
+        assert_eq!(raw[0].value.as_f64(), Some(3.68));
+        assert_eq!(raw[1].value.as_f64(), Some(12.0));
+        assert_eq!(raw[2].observation_id, ObservationId::Unknown(999));
+    }
+}
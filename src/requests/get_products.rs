@@ -0,0 +1,103 @@
+use crate::{Client, JsonBody, NoBody, Result, SiteProducts};
+
+/// Fetches every site of the account with its circuits, chargers and equalizers in one call
+pub struct GetProducts;
+
+impl GetProducts {
+    pub async fn send(&self, client: &Client) -> Result<Vec<SiteProducts>> {
+        client
+            .req::<_, JsonBody<Vec<SiteProducts>>>(
+                http::Method::GET,
+                "api/accounts/products",
+                NoBody,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CircuitId, SiteId, SiteProducts};
+
+    #[test]
+    fn deserialize() {
+        let s = r#"
+[
+  {
+    "id": 575766,
+    "siteKey": "EJ7L-E622",
+    "name": "Brf Lindblomman 213",
+    "levelOfAccess": 3,
+    "address": {
+      "street": "Beckombergavägen",
+      "buildingNumber": "213",
+      "zip": "168 61",
+      "area": "Bromma",
+      "country": { "id": "SE", "name": "Sweden", "phonePrefix": 0 },
+      "latitude": null,
+      "longitude": null,
+      "altitude": null
+    },
+    "circuits": [
+      {
+        "id": 544874,
+        "siteId": 575766,
+        "circuitPanelId": 1,
+        "panelName": "Parkering 11-16",
+        "ratedCurrent": 32.0,
+        "chargers": [
+          {
+            "id": "EC9TVZ6L",
+            "name": "Parkering 16",
+            "color": null,
+            "createdOn": "2022-09-14T16:13:11.719836",
+            "updatedOn": "2023-01-12T13:37:40.639359",
+            "backPlate": {
+              "id": "80731B72BEB004",
+              "masterBackPlateId": "8072B5B2406C04",
+              "name": "Parkering 16",
+              "features": []
+            },
+            "levelOfAccess": 1,
+            "productCode": 100,
+            "userRole": 2,
+            "isTemporary": false
+          }
+        ],
+        "masterBackplate": null,
+        "useDynamicMaster": false,
+        "parentCircuitId": null
+      }
+    ],
+    "equalizers": [
+      { "id": "QPTJH7UN", "name": "QPTJH7UN", "siteId": 575766, "circuitId": null }
+    ]
+  },
+  {
+    "id": 85096,
+    "siteKey": "E6US-N222",
+    "name": "Brf Ryssjan 222",
+    "levelOfAccess": null,
+    "address": {
+      "street": null,
+      "buildingNumber": null,
+      "zip": null,
+      "area": null,
+      "country": null,
+      "latitude": null,
+      "longitude": null,
+      "altitude": null
+    }
+  }
+]
+"#;
+
+        let products = serde_json::from_str::<Vec<SiteProducts>>(s).expect("deserializing");
+
+        assert_eq!(products[0].site.id, SiteId(575766));
+        assert_eq!(products[0].circuits[0].id, CircuitId(544874));
+        assert_eq!(products[0].chargers().count(), 1);
+        assert_eq!(products[0].equalizers[0].site_id, SiteId(575766));
+        assert!(products[1].circuits.is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{
+    ChargerConsumption, Client, Error, JsonBody, NoBody, Result, SiteConsumption, SiteId,
+    UserConsumption,
+};
+
+/// Date range shared by the site consumption requests. `to` is exclusive.
+struct Period {
+    site_id: SiteId,
+    from: time::Date,
+    to: time::Date,
+}
+
+impl Period {
+    fn url(&self, kind: &str) -> Result<String> {
+        if self.from >= self.to {
+            return Err(Error::InvalidRequest(format!(
+                "from {} must be before to {}",
+                self.from, self.to
+            )));
+        }
+
+        let df = time::macros::format_description!("[year]-[month]-[day]");
+
+        let site_id = self.site_id;
+        let from_s = self.from.format(&df).unwrap();
+        let to_s = self.to.format(&df).unwrap();
+
+        Ok(format!(
+            "api/sites/{site_id}/consumption{kind}/{from_s}/{to_s}"
+        ))
+    }
+}
+
+/// Fetches the total consumption of a site between [from - to[
+///
+/// So to fetch the consumption of march 2025:
+/// * from:  2025-03-01
+/// * to:    2025-04-01
+pub struct GetSiteConsumption(Period);
+
+impl GetSiteConsumption {
+    pub fn new(site_id: SiteId, from_inclusive: time::Date, to_exclusive: time::Date) -> Self {
+        Self(Period {
+            site_id,
+            from: from_inclusive,
+            to: to_exclusive,
+        })
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<SiteConsumption> {
+        let url = self.0.url("")?;
+
+        client
+            .req::<_, JsonBody<SiteConsumption>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+/// Fetches the consumption per user of a site between [from - to[
+pub struct GetSiteUserConsumption(Period);
+
+impl GetSiteUserConsumption {
+    pub fn new(site_id: SiteId, from_inclusive: time::Date, to_exclusive: time::Date) -> Self {
+        Self(Period {
+            site_id,
+            from: from_inclusive,
+            to: to_exclusive,
+        })
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Vec<UserConsumption>> {
+        let url = self.0.url("/users")?;
+
+        client
+            .req::<_, JsonBody<Vec<UserConsumption>>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+/// Fetches the consumption per charger of a site between [from - to[
+pub struct GetSiteChargerConsumption(Period);
+
+impl GetSiteChargerConsumption {
+    pub fn new(site_id: SiteId, from_inclusive: time::Date, to_exclusive: time::Date) -> Self {
+        Self(Period {
+            site_id,
+            from: from_inclusive,
+            to: to_exclusive,
+        })
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Vec<ChargerConsumption>> {
+        let url = self.0.url("/chargers")?;
+
+        client
+            .req::<_, JsonBody<Vec<ChargerConsumption>>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url() {
+        let req = GetSiteUserConsumption::new(
+            SiteId(85096),
+            time::macros::date!(2025 - 03 - 01),
+            time::macros::date!(2025 - 04 - 01),
+        );
+
+        assert_eq!(
+            req.0.url("/users").expect("valid"),
+            "api/sites/85096/consumption/users/2025-03-01/2025-04-01"
+        );
+
+        GetSiteConsumption::new(
+            SiteId(85096),
+            time::macros::date!(2025 - 04 - 01),
+            time::macros::date!(2025 - 04 - 01),
+        )
+        .0
+        .url("")
+        .expect_err("empty range");
+    }
+
+    #[test]
+    fn deserialize() {
+        let s = r#"
+{
+  "siteId": 85096,
+  "from": "2025-03-01T00:00:00Z",
+  "to": "2025-04-01T00:00:00Z",
+  "totalEnergy": 147.6,
+  "costExcludingVat": 258.6,
+  "costIncludingVat": 323.25,
+  "currencyId": "SEK",
+  "sessionCount": 8
+}
+"#;
+        let total = serde_json::from_str::<SiteConsumption>(s).expect("deserializing");
+        assert_eq!(total.site_id, SiteId(85096));
+        assert_eq!(total.kwh, 147.6);
+        assert_eq!(total.currency.as_deref(), Some("SEK"));
+
+        let s = r#"
+[
+  { "chargerId": "EC32KXFN", "chargerName": "Höger", "totalEnergy": 83.84, "costExcludingVat": 146.89, "costIncludingVat": 183.64, "sessionCount": 6 },
+  { "chargerId": "ECS7ZRJY", "chargerName": null, "totalEnergy": 63.66, "costExcludingVat": null, "costIncludingVat": null, "sessionCount": null }
+]
+"#;
+        serde_json::from_str::<Vec<ChargerConsumption>>(s).expect("deserializing");
+    }
+}
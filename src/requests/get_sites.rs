@@ -1,9 +1,16 @@
-use crate::{Client, JsonBody, NoBody, Result, SiteSub};
+use std::collections::VecDeque;
 
-#[derive(Default, Clone)]
+use crate::{Client, Error, JsonBody, QueryParams, Result, SiteId, SiteSub};
+
+static DEFAULT_PAGE_SIZE: u32 = 100;
+
+#[derive(Default, Clone, serde::Serialize)]
 pub struct GetSites {
+    #[serde(skip_serializing_if = "Option::is_none")]
     search: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     limit: Option<u32>,
 }
 
@@ -25,14 +32,145 @@ impl GetSites {
 
     pub async fn send(&self, client: &Client) -> Result<Vec<SiteSub>> {
         client
-            .req::<_, JsonBody<Vec<SiteSub>>>(http::Method::GET, "api/sites", NoBody)
+            .req::<_, JsonBody<Vec<SiteSub>>>(http::Method::GET, "api/sites", QueryParams(self))
             .await
     }
+
+    /// Walks through all matching sites, fetching one page at a time as needed.
+    ///
+    /// Starts at `offset` (if set) and asks for `limit` sites per page (defaults to 100). The
+    /// server may return fewer, so only an empty page ends the listing.
+    pub fn pages(self) -> SitePages {
+        let page_size = self.limit.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+
+        SitePages {
+            req: self.limit(page_size),
+            buffered: VecDeque::new(),
+            previous: Vec::new(),
+            done: false,
+        }
+    }
+}
+
+/// Lazily paginates through `GetSites`, see `GetSites::pages`.
+///
+/// A cursor taking the client on every call rather than a `Stream`, like the requests it's built
+/// on, so it neither holds on to a `Client` nor needs `futures` outside the streaming feature.
+pub struct SitePages {
+    req: GetSites,
+    buffered: VecDeque<SiteSub>,
+    /// Ids of the previous page, to notice a server ignoring `offset`
+    previous: Vec<SiteId>,
+    done: bool,
+}
+
+impl SitePages {
+    /// Fetches the next page, `None` once all sites have been received
+    pub async fn next_page(&mut self, client: &Client) -> Result<Option<Vec<SiteSub>>> {
+        if !self.buffered.is_empty() {
+            return Ok(Some(self.buffered.drain(..).collect()));
+        }
+
+        if self.done {
+            return Ok(None);
+        }
+
+        let page = self.req.send(client).await?;
+        self.advance(page)
+    }
+
+    fn advance(&mut self, page: Vec<SiteSub>) -> Result<Option<Vec<SiteSub>>> {
+        if page.is_empty() {
+            self.done = true;
+            return Ok(None);
+        }
+
+        let ids = page.iter().map(|site| site.id).collect::<Vec<_>>();
+        if ids == self.previous {
+            self.done = true;
+            return Err(Error::InvalidResponse(format!(
+                "sites at offset {} repeat the previous page",
+                self.req.offset.unwrap_or(0)
+            )));
+        }
+
+        self.previous = ids;
+        self.req.offset = Some(self.req.offset.unwrap_or(0) + page.len() as u32);
+
+        Ok(Some(page))
+    }
+
+    /// Returns the next site, fetching another page when the current one is used up
+    pub async fn next(&mut self, client: &Client) -> Result<Option<SiteSub>> {
+        if self.buffered.is_empty() {
+            match self.next_page(client).await? {
+                Some(page) => self.buffered.extend(page),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(self.buffered.pop_front())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::SiteSub;
+    use super::GetSites;
+    use crate::{Error, SiteSub};
+
+    fn page(ids: &[i64]) -> Vec<SiteSub> {
+        ids.iter()
+            .map(|id| {
+                serde_json::from_value(serde_json::json!({
+                    "id": id,
+                    "siteKey": "",
+                    "name": "",
+                    "levelOfAccess": null,
+                    "address": {},
+                }))
+                .expect("site")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pagination() {
+        let mut pages = GetSites::default().pages();
+
+        // A page capped below the requested 100 doesn't end the listing
+        assert_eq!(
+            pages.advance(page(&[1, 2])).expect("page").map(|p| p.len()),
+            Some(2)
+        );
+        assert_eq!(pages.req.offset, Some(2));
+        assert!(!pages.done);
+
+        assert!(pages.advance(page(&[3])).expect("page").is_some());
+        assert_eq!(pages.advance(vec![]).expect("page"), None);
+        assert!(pages.done);
+
+        // A server ignoring the offset sends the same page again
+        let mut pages = GetSites::default().pages();
+        pages.advance(page(&[1, 2])).expect("page");
+        assert!(matches!(
+            pages.advance(page(&[1, 2])),
+            Err(Error::InvalidResponse(_))
+        ));
+        assert!(pages.done);
+    }
+
+    #[test]
+    fn query_params() {
+        let req = GetSites::default().search("Brf").limit(10);
+        assert_eq!(
+            serde_json::to_value(&req).expect("serializing"),
+            serde_json::json!({ "search": "Brf", "limit": 10 })
+        );
+
+        let pages = GetSites::default().offset(20).pages();
+        assert_eq!(pages.req.limit, Some(100));
+        assert_eq!(pages.req.offset, Some(20));
+    }
 
     #[test]
     fn deserialize_resp() {
@@ -1,11 +1,33 @@
+mod account;
+mod authorization_tokens;
+mod charge_plan;
+mod charger_access;
+mod charger_command;
+mod circuit_dynamic_current;
+mod dynamic_charger_current;
+mod equalizer;
 mod get_charger_sessions;
+mod get_charger_state;
+mod get_charger_usage;
+mod get_firmware;
+mod get_observations;
 mod get_ongoing_session;
+mod get_products;
 mod get_profile;
 mod get_site;
+mod get_site_consumption;
 mod get_sites;
 mod get_user_sessions;
+mod site_admin;
+mod site_users;
+mod update_circuit_settings;
+mod update_site_price;
 
 pub use {
-    get_charger_sessions::*, get_ongoing_session::*, get_profile::*, get_site::*, get_sites::*,
-    get_user_sessions::*,
+    account::*, authorization_tokens::*, charge_plan::*, charger_access::*, charger_command::*,
+    circuit_dynamic_current::*, dynamic_charger_current::*, equalizer::*, get_charger_sessions::*,
+    get_charger_state::*, get_charger_usage::*, get_firmware::*, get_observations::*,
+    get_ongoing_session::*, get_products::*, get_profile::*, get_site::*, get_site_consumption::*,
+    get_sites::*, get_user_sessions::*, site_admin::*, site_users::*, update_circuit_settings::*,
+    update_site_price::*,
 };
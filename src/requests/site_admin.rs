@@ -0,0 +1,466 @@
+use crate::{
+    Address, BytesBody, Circuit, CircuitId, Client, Error, JsonBody, NoBody, Result, Site,
+};
+
+fn invalid<T>(msg: String) -> Result<T> {
+    Err(Error::InvalidRequest(msg))
+}
+
+fn validate_current(what: &str, amps: f64) -> Result<()> {
+    if !amps.is_finite() || amps <= 0.0 {
+        return invalid(format!(
+            "{what} must be a positive number of amps, got {amps}"
+        ));
+    }
+    Ok(())
+}
+
+fn find_circuit(site: &Site, circuit_id: CircuitId) -> Result<&Circuit> {
+    match site.circuits.iter().find(|c| c.id == circuit_id) {
+        Some(circuit) => Ok(circuit),
+        None => invalid(format!(
+            "circuit {circuit_id} is not part of site {}",
+            site.id
+        )),
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSite {
+    name: String,
+    address: Address,
+    rated_current: f64,
+    site_type: u32,
+}
+
+impl CreateSite {
+    /// `site_type` as reported in `Site::site_type`, eg 100
+    pub fn new(
+        name: impl Into<String>,
+        address: Address,
+        rated_current: f64,
+        site_type: u32,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            address,
+            rated_current,
+            site_type,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return invalid("site name must not be empty".into());
+        }
+        validate_current("rated current", self.rated_current)
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<Site> {
+        self.validate()?;
+
+        client
+            .req::<_, JsonBody<Site>>(http::Method::POST, "api/sites", JsonBody(self))
+            .await
+    }
+}
+
+/// Updates the given fields of an existing site
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSite {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rated_current: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    site_type: Option<u32>,
+}
+
+impl UpdateSite {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn rated_current(mut self, amps: f64) -> Self {
+        self.rated_current = Some(amps);
+        self
+    }
+
+    pub fn site_type(mut self, site_type: u32) -> Self {
+        self.site_type = Some(site_type);
+        self
+    }
+
+    fn validate(&self, site: &Site) -> Result<()> {
+        let UpdateSite {
+            name,
+            address,
+            rated_current,
+            site_type,
+        } = self;
+
+        if name.is_none() && address.is_none() && rated_current.is_none() && site_type.is_none() {
+            return invalid("no site fields to update".into());
+        }
+
+        if let Some(name) = name
+            && name.trim().is_empty()
+        {
+            return invalid("site name must not be empty".into());
+        }
+
+        if let Some(rated) = *rated_current {
+            validate_current("rated current", rated)?;
+
+            if let Some(circuit) = site.circuits.iter().find(|c| c.rated_current > rated) {
+                return invalid(format!(
+                    "rated current {rated}A is below the {}A of circuit {}",
+                    circuit.rated_current, circuit.id
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, client: &Client, site: &Site) -> Result<()> {
+        self.validate(site)?;
+
+        let site_id = site.id;
+        let url = format!("api/sites/{site_id}");
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(self))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCircuit {
+    circuit_panel_id: i64,
+    panel_name: String,
+    rated_current: f64,
+}
+
+impl CreateCircuit {
+    pub fn new(circuit_panel_id: i64, panel_name: impl Into<String>, rated_current: f64) -> Self {
+        Self {
+            circuit_panel_id,
+            panel_name: panel_name.into(),
+            rated_current,
+        }
+    }
+
+    fn validate(&self, site: &Site) -> Result<()> {
+        validate_current("rated current", self.rated_current)?;
+
+        if let Some(circuit) = site
+            .circuits
+            .iter()
+            .find(|c| c.circuit_panel_id == self.circuit_panel_id)
+        {
+            return invalid(format!(
+                "panel id {} is already used by circuit {}",
+                self.circuit_panel_id, circuit.id
+            ));
+        }
+
+        // Sites that haven't been configured report a rated current of 0
+        if site.rated_current > 0.0 && self.rated_current > site.rated_current {
+            return invalid(format!(
+                "{}A exceeds the rated current {}A of site {}",
+                self.rated_current, site.rated_current, site.id
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, client: &Client, site: &Site) -> Result<Circuit> {
+        self.validate(site)?;
+
+        let site_id = site.id;
+        let url = format!("api/sites/{site_id}/circuits");
+
+        client
+            .req::<_, JsonBody<Circuit>>(http::Method::POST, &url, JsonBody(self))
+            .await
+    }
+}
+
+/// Deletes a circuit, which must not have any chargers left
+pub struct DeleteCircuit(pub CircuitId);
+
+impl DeleteCircuit {
+    fn validate(&self, site: &Site) -> Result<()> {
+        let circuit = find_circuit(site, self.0)?;
+
+        if !circuit.chargers.is_empty() {
+            return invalid(format!(
+                "circuit {} still has {} charger(s) paired",
+                circuit.id,
+                circuit.chargers.len()
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, client: &Client, site: &Site) -> Result<()> {
+        self.validate(site)?;
+
+        let site_id = site.id;
+        let circuit_id = self.0;
+        let url = format!("api/sites/{site_id}/circuits/{circuit_id}");
+
+        client
+            .req::<_, BytesBody>(http::Method::DELETE, &url, NoBody)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Pairs a charger to a circuit using the serial and PIN printed on the charger
+pub struct PairCharger {
+    circuit_id: CircuitId,
+    body: PairChargerBody,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PairChargerBody {
+    charger_id: String,
+    pin_code: String,
+}
+
+impl PairCharger {
+    pub fn new(circuit_id: CircuitId, serial: impl Into<String>, pin: impl Into<String>) -> Self {
+        Self {
+            circuit_id,
+            body: PairChargerBody {
+                charger_id: serial.into().trim().to_ascii_uppercase(),
+                pin_code: pin.into().trim().to_owned(),
+            },
+        }
+    }
+
+    fn validate(&self, site: &Site) -> Result<()> {
+        let serial = &self.body.charger_id;
+        let pin = &self.body.pin_code;
+
+        if serial.len() != 8 || !serial.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return invalid(format!("invalid charger serial `{serial}`"));
+        }
+
+        if pin.len() != 4 || !pin.chars().all(|c| c.is_ascii_digit()) {
+            return invalid("charger PIN must be 4 digits".into());
+        }
+
+        find_circuit(site, self.circuit_id)?;
+
+        if let Some(circuit) = site.circuit_for_charger(serial) {
+            return invalid(format!(
+                "charger {serial} is already paired to circuit {}",
+                circuit.id
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, client: &Client, site: &Site) -> Result<()> {
+        self.validate(site)?;
+
+        let site_id = site.id;
+        let circuit_id = self.circuit_id;
+        let url = format!("api/sites/{site_id}/circuits/{circuit_id}/chargers");
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&self.body))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Removes a charger from the circuit it is paired to
+pub struct UnpairCharger {
+    charger_id: String,
+}
+
+impl UnpairCharger {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+        }
+    }
+
+    pub async fn send(&self, client: &Client, site: &Site) -> Result<()> {
+        let charger_id = &self.charger_id;
+
+        let Some(circuit) = site.circuit_for_charger(charger_id) else {
+            return invalid(format!(
+                "charger {charger_id} is not part of site {}",
+                site.id
+            ));
+        };
+
+        let site_id = site.id;
+        let circuit_id = circuit.id;
+        let url = format!("api/sites/{site_id}/circuits/{circuit_id}/chargers/{charger_id}");
+
+        client
+            .req::<_, BytesBody>(http::Method::DELETE, &url, NoBody)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site() -> Site {
+        let s = r#"
+{
+  "id": 1,
+  "siteKey": "AAAA-1111",
+  "name": "Test",
+  "levelOfAccess": null,
+  "address": { "street": null, "buildingNumber": null, "zip": null, "area": null, "country": null, "latitude": null, "longitude": null, "altitude": null },
+  "contactInfo": null,
+  "costPerKWh": null,
+  "costPerKwhExcludeVat": null,
+  "currencyId": null,
+  "siteType": 100,
+  "ratedCurrent": 63.0,
+  "vat": null,
+  "partnerId": null,
+  "installerId": null,
+  "useDynamicMaster": false,
+  "circuits": [
+    {
+      "id": 10,
+      "siteId": 1,
+      "circuitPanelId": 1,
+      "panelName": "1",
+      "ratedCurrent": 20.0,
+      "chargers": [
+        {
+          "id": "EC000001",
+          "name": "Charger",
+          "color": null,
+          "createdOn": "2022-10-12T05:01:39.518305",
+          "updatedOn": "2023-01-12T14:58:32.612267",
+          "backPlate": { "id": "1", "masterBackPlateId": "1", "name": "", "features": [] },
+          "levelOfAccess": null,
+          "productCode": 100,
+          "userRole": null,
+          "isTemporary": false
+        }
+      ],
+      "masterBackplate": null,
+      "useDynamicMaster": false,
+      "parentCircuitId": null
+    },
+    {
+      "id": 11,
+      "siteId": 1,
+      "circuitPanelId": 2,
+      "panelName": "2",
+      "ratedCurrent": 16.0,
+      "chargers": [],
+      "masterBackplate": null,
+      "useDynamicMaster": false,
+      "parentCircuitId": null
+    }
+  ],
+  "equalizers": [],
+  "createdOn": "2023-01-12T14:44:57.04698",
+  "updatedOn": "2023-03-30T08:17:59.292387",
+  "userRole": null,
+  "allowedSiteActions": []
+}
+"#;
+        serde_json::from_str(s).expect("deserializing site")
+    }
+
+    #[test]
+    fn validate_circuits() {
+        let site = site();
+
+        CreateCircuit::new(3, "3", 25.0)
+            .validate(&site)
+            .expect("valid");
+        CreateCircuit::new(1, "1b", 25.0)
+            .validate(&site)
+            .expect_err("panel id taken");
+        CreateCircuit::new(3, "3", 80.0)
+            .validate(&site)
+            .expect_err("exceeds site");
+
+        DeleteCircuit(CircuitId(11))
+            .validate(&site)
+            .expect("empty circuit");
+        DeleteCircuit(CircuitId(10))
+            .validate(&site)
+            .expect_err("has chargers");
+        DeleteCircuit(CircuitId(99))
+            .validate(&site)
+            .expect_err("unknown circuit");
+    }
+
+    #[test]
+    fn validate_pairing() {
+        let site = site();
+
+        PairCharger::new(CircuitId(11), "ec000002", "1234")
+            .validate(&site)
+            .expect("valid");
+        PairCharger::new(CircuitId(11), "EC000001", "1234")
+            .validate(&site)
+            .expect_err("already paired");
+        PairCharger::new(CircuitId(11), "EC000002", "12a4")
+            .validate(&site)
+            .expect_err("invalid pin");
+        PairCharger::new(CircuitId(99), "EC000002", "1234")
+            .validate(&site)
+            .expect_err("unknown circuit");
+    }
+
+    #[test]
+    fn validate_site_update() {
+        let site = site();
+
+        UpdateSite::default()
+            .rated_current(40.0)
+            .validate(&site)
+            .expect("valid");
+        UpdateSite::default()
+            .rated_current(18.0)
+            .validate(&site)
+            .expect_err("below circuit");
+        UpdateSite::default()
+            .name(" ")
+            .validate(&site)
+            .expect_err("empty name");
+        UpdateSite::default()
+            .validate(&site)
+            .expect_err("nothing to update");
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{BytesBody, Client, Error, JsonBody, NoBody, Result, SiteId, SiteUser, UserSiteRole};
+
+pub struct GetSiteUsers(pub SiteId);
+
+impl GetSiteUsers {
+    pub async fn send(&self, client: &Client) -> Result<Vec<SiteUser>> {
+        let site_id = self.0;
+        let url = format!("api/sites/{site_id}/users");
+
+        client
+            .req::<_, JsonBody<Vec<SiteUser>>>(http::Method::GET, &url, NoBody)
+            .await
+    }
+}
+
+/// Invites a user to a site, identified by either email or phone number.
+/// Users without an Easee account are invited to create one.
+pub struct AddSiteUser {
+    site_id: SiteId,
+    body: AddSiteUserBody,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddSiteUserBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[serde(rename = "phoneNo", skip_serializing_if = "Option::is_none")]
+    phone_nr: Option<String>,
+    role_id: UserSiteRole,
+}
+
+impl AddSiteUser {
+    pub fn email(site_id: SiteId, email: impl Into<String>, role: UserSiteRole) -> Self {
+        Self {
+            site_id,
+            body: AddSiteUserBody {
+                email: Some(email.into()),
+                phone_nr: None,
+                role_id: role,
+            },
+        }
+    }
+
+    /// `phone_nr` including country prefix, eg `+46701234567`
+    pub fn phone(site_id: SiteId, phone_nr: impl Into<String>, role: UserSiteRole) -> Self {
+        Self {
+            site_id,
+            body: AddSiteUserBody {
+                email: None,
+                phone_nr: Some(phone_nr.into()),
+                role_id: role,
+            },
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(email) = &self.body.email
+            && !email.contains('@')
+        {
+            return Err(Error::InvalidRequest(format!("invalid email `{email}`")));
+        }
+
+        if let Some(phone_nr) = &self.body.phone_nr
+            && !phone_nr.starts_with('+')
+        {
+            return Err(Error::InvalidRequest(format!(
+                "phone number `{phone_nr}` must include country prefix"
+            )));
+        }
+
+        if let UserSiteRole::Unknown(n) = self.body.role_id {
+            return Err(Error::InvalidRequest(format!("unknown role {n}")));
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        self.validate()?;
+
+        let site_id = self.site_id;
+        let url = format!("api/sites/{site_id}/users");
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&self.body))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Gives a site user a new role, revoking the previous one
+pub struct SetSiteUserRole {
+    site_id: SiteId,
+    user_id: i64,
+    from: UserSiteRole,
+    to: UserSiteRole,
+}
+
+impl SetSiteUserRole {
+    pub fn new(site_id: SiteId, user_id: i64, from: UserSiteRole, to: UserSiteRole) -> Self {
+        Self {
+            site_id,
+            user_id,
+            from,
+            to,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        // Both roles have to be known before the new one is added, or the user could be left
+        // with a garbage role or without the old one
+        for role in [self.from, self.to] {
+            if let UserSiteRole::Unknown(n) = role {
+                return Err(Error::InvalidRequest(format!("unknown role {n}")));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        self.validate()?;
+
+        if self.from == self.to {
+            return Ok(());
+        }
+
+        let site_id = self.site_id;
+        let user_id = self.user_id;
+        let to = u8::from(self.to);
+        let from = u8::from(self.from);
+
+        client
+            .req::<_, BytesBody>(
+                http::Method::POST,
+                &format!("api/sites/{site_id}/users/{user_id}/roles/{to}"),
+                NoBody,
+            )
+            .await?;
+
+        client
+            .req::<_, BytesBody>(
+                http::Method::DELETE,
+                &format!("api/sites/{site_id}/users/{user_id}/roles/{from}"),
+                NoBody,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+pub struct RemoveSiteUser {
+    site_id: SiteId,
+    user_id: i64,
+}
+
+impl RemoveSiteUser {
+    pub fn new(site_id: SiteId, user_id: i64) -> Self {
+        Self { site_id, user_id }
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        let site_id = self.site_id;
+        let user_id = self.user_id;
+        let url = format!("api/sites/{site_id}/users/{user_id}");
+
+        client
+            .req::<_, BytesBody>(http::Method::DELETE, &url, NoBody)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_users() {
+        let s = r#"
+[
+  { "userId": 265514, "name": "Test Testsson", "email": "test@example.com", "phoneNo": "+46700000000", "roleId": 2 },
+  { "userId": 265515, "name": null, "email": null, "phoneNo": "+46700000001", "roleId": 1 }
+]
+"#;
+
+        let users = serde_json::from_str::<Vec<SiteUser>>(s).expect("deserializing");
+        assert_eq!(users[0].role, UserSiteRole::Admin);
+        assert_eq!(users[1].role, UserSiteRole::User);
+    }
+
+    #[test]
+    fn validate_add_user() {
+        AddSiteUser::email(SiteId(1), "test@example.com", UserSiteRole::User)
+            .validate()
+            .expect("valid");
+        AddSiteUser::email(SiteId(1), "test", UserSiteRole::User)
+            .validate()
+            .expect_err("invalid email");
+        AddSiteUser::phone(SiteId(1), "0701234567", UserSiteRole::User)
+            .validate()
+            .expect_err("missing country prefix");
+    }
+
+    #[test]
+    fn validate_set_role() {
+        SetSiteUserRole::new(SiteId(1), 2, UserSiteRole::User, UserSiteRole::Admin)
+            .validate()
+            .expect("valid");
+        SetSiteUserRole::new(SiteId(1), 2, UserSiteRole::Unknown(9), UserSiteRole::Admin)
+            .validate()
+            .expect_err("unknown from");
+        SetSiteUserRole::new(SiteId(1), 2, UserSiteRole::User, UserSiteRole::Unknown(9))
+            .validate()
+            .expect_err("unknown to");
+    }
+}
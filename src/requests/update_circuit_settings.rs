@@ -0,0 +1,219 @@
+use crate::{BytesBody, CircuitId, Client, Error, JsonBody, Result, SiteId};
+
+/// Updates the static limits of a circuit.
+///
+/// Only the fields that have been set are sent. Rated current and panel name live on the
+/// circuit itself while the phase limits are circuit settings, so up to two requests are made.
+#[derive(Clone)]
+pub struct UpdateCircuitSettings {
+    site_id: SiteId,
+    circuit_id: CircuitId,
+    circuit: CircuitBody,
+    settings: CircuitSettingsBody,
+}
+
+#[derive(Default, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CircuitBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rated_current: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    panel_name: Option<String>,
+}
+
+#[derive(Default, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CircuitSettingsBody {
+    #[serde(
+        rename = "maxCircuitCurrentP1",
+        skip_serializing_if = "Option::is_none"
+    )]
+    max_p1: Option<f64>,
+    #[serde(
+        rename = "maxCircuitCurrentP2",
+        skip_serializing_if = "Option::is_none"
+    )]
+    max_p2: Option<f64>,
+    #[serde(
+        rename = "maxCircuitCurrentP3",
+        skip_serializing_if = "Option::is_none"
+    )]
+    max_p3: Option<f64>,
+
+    #[serde(
+        rename = "offlineMaxCircuitCurrentP1",
+        skip_serializing_if = "Option::is_none"
+    )]
+    offline_max_p1: Option<f64>,
+    #[serde(
+        rename = "offlineMaxCircuitCurrentP2",
+        skip_serializing_if = "Option::is_none"
+    )]
+    offline_max_p2: Option<f64>,
+    #[serde(
+        rename = "offlineMaxCircuitCurrentP3",
+        skip_serializing_if = "Option::is_none"
+    )]
+    offline_max_p3: Option<f64>,
+}
+
+impl CircuitBody {
+    fn is_empty(&self) -> bool {
+        self.rated_current.is_none() && self.panel_name.is_none()
+    }
+}
+
+impl CircuitSettingsBody {
+    fn is_empty(&self) -> bool {
+        self.max()
+            .iter()
+            .chain(self.offline_max().iter())
+            .all(Option::is_none)
+    }
+
+    fn max(&self) -> [Option<f64>; 3] {
+        [self.max_p1, self.max_p2, self.max_p3]
+    }
+
+    fn offline_max(&self) -> [Option<f64>; 3] {
+        [
+            self.offline_max_p1,
+            self.offline_max_p2,
+            self.offline_max_p3,
+        ]
+    }
+}
+
+impl UpdateCircuitSettings {
+    pub fn new(site_id: SiteId, circuit_id: CircuitId) -> Self {
+        Self {
+            site_id,
+            circuit_id,
+            circuit: CircuitBody::default(),
+            settings: CircuitSettingsBody::default(),
+        }
+    }
+
+    pub fn rated_current(mut self, amps: f64) -> Self {
+        self.circuit.rated_current = Some(amps);
+        self
+    }
+
+    pub fn panel_name(mut self, name: impl Into<String>) -> Self {
+        self.circuit.panel_name = Some(name.into());
+        self
+    }
+
+    pub fn max_phase_currents(mut self, p1: f64, p2: f64, p3: f64) -> Self {
+        self.settings.max_p1 = Some(p1);
+        self.settings.max_p2 = Some(p2);
+        self.settings.max_p3 = Some(p3);
+        self
+    }
+
+    /// Limits used by the chargers when they lose connection to the cloud
+    pub fn offline_max_phase_currents(mut self, p1: f64, p2: f64, p3: f64) -> Self {
+        self.settings.offline_max_p1 = Some(p1);
+        self.settings.offline_max_p2 = Some(p2);
+        self.settings.offline_max_p3 = Some(p3);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.circuit.is_empty() && self.settings.is_empty() {
+            return Err(Error::InvalidRequest(
+                "no circuit settings to update".into(),
+            ));
+        }
+
+        let rated = self.circuit.rated_current;
+
+        let currents = [rated]
+            .into_iter()
+            .chain(self.settings.max())
+            .chain(self.settings.offline_max())
+            .flatten();
+
+        for amps in currents {
+            if !amps.is_finite() || amps < 0.0 {
+                return Err(Error::InvalidRequest(format!(
+                    "circuit current must be positive, got {amps}A"
+                )));
+            }
+        }
+
+        if let Some(rated) = rated {
+            let limits = self
+                .settings
+                .max()
+                .into_iter()
+                .chain(self.settings.offline_max());
+
+            if let Some(amps) = limits.flatten().find(|amps| *amps > rated) {
+                return Err(Error::InvalidRequest(format!(
+                    "phase limit {amps}A exceeds the rated current {rated}A"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<()> {
+        self.validate()?;
+
+        let site_id = self.site_id;
+        let circuit_id = self.circuit_id;
+
+        if !self.circuit.is_empty() {
+            let url = format!("api/sites/{site_id}/circuits/{circuit_id}");
+            client
+                .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&self.circuit))
+                .await?;
+        }
+
+        if !self.settings.is_empty() {
+            let url = format!("api/sites/{site_id}/circuits/{circuit_id}/settings");
+            client
+                .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&self.settings))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let req = UpdateCircuitSettings::new(SiteId(1), CircuitId(2));
+        req.validate().expect_err("nothing to update");
+
+        req.clone()
+            .rated_current(32.0)
+            .max_phase_currents(32.0, 32.0, 25.0)
+            .validate()
+            .expect("valid");
+
+        req.clone()
+            .rated_current(20.0)
+            .offline_max_phase_currents(25.0, 6.0, 6.0)
+            .validate()
+            .expect_err("offline limit exceeds rated current");
+    }
+
+    #[test]
+    fn serialize_settings() {
+        let req = UpdateCircuitSettings::new(SiteId(1), CircuitId(2))
+            .offline_max_phase_currents(6.0, 6.0, 6.0);
+
+        assert_eq!(
+            serde_json::to_string(&req.settings).expect("serializing"),
+            r#"{"offlineMaxCircuitCurrentP1":6.0,"offlineMaxCircuitCurrentP2":6.0,"offlineMaxCircuitCurrentP3":6.0}"#
+        );
+        assert!(req.circuit.is_empty());
+    }
+}
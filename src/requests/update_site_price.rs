@@ -0,0 +1,176 @@
+use crate::{BytesBody, Client, Error, JsonBody, Result, SiteId};
+
+/// The price configuration sent to Easee, with both the excl. and incl. VAT price set.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SitePrice {
+    pub currency_id: String,
+    pub vat: f64,
+    #[serde(rename = "costPerKWh")]
+    pub cost_per_kwh: f64,
+    pub cost_per_kwh_exclude_vat: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Price {
+    IncludingVat(f64),
+    ExcludingVat(f64),
+}
+
+/// Sets the price per kWh of a site.
+///
+/// Give either the price including or excluding VAT, the other one is derived from `vat`.
+#[derive(Debug, Clone)]
+pub struct UpdateSitePrice {
+    site_id: SiteId,
+    currency_id: String,
+    vat: f64,
+    price: Option<Price>,
+}
+
+impl UpdateSitePrice {
+    /// `currency_id` is the ISO 4217 code, eg `SEK`
+    pub fn new(site_id: SiteId, currency_id: impl Into<String>) -> Self {
+        Self {
+            site_id,
+            currency_id: currency_id.into(),
+            vat: 0.0,
+            price: None,
+        }
+    }
+
+    /// VAT in percent, eg `25.0`
+    pub fn vat(mut self, vat: f64) -> Self {
+        self.vat = vat;
+        self
+    }
+
+    pub fn price_including_vat(mut self, price: f64) -> Self {
+        self.price = Some(Price::IncludingVat(price));
+        self
+    }
+
+    pub fn price_excluding_vat(mut self, price: f64) -> Self {
+        self.price = Some(Price::ExcludingVat(price));
+        self
+    }
+
+    /// Validates the combination and computes the price counterpart
+    pub fn site_price(&self) -> Result<SitePrice> {
+        let currency_id = self.currency_id.trim();
+        if currency_id.len() != 3 || !currency_id.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::InvalidRequest(format!(
+                "currency must be a 3 letter ISO 4217 code, got `{currency_id}`"
+            )));
+        }
+
+        let vat = self.vat;
+        if !vat.is_finite() || !(0.0..100.0).contains(&vat) {
+            return Err(Error::InvalidRequest(format!(
+                "vat must be a percentage between 0 and 100, got {vat}"
+            )));
+        }
+
+        let price = self
+            .price
+            .ok_or_else(|| Error::InvalidRequest("no price set".into()))?;
+
+        let factor = 1.0 + vat / 100.0;
+
+        let (incl, excl) = match price {
+            Price::IncludingVat(incl) => (incl, round(incl / factor)),
+            Price::ExcludingVat(excl) => (round(excl * factor), excl),
+        };
+
+        if !incl.is_finite() || !excl.is_finite() || incl < 0.0 || excl < 0.0 {
+            return Err(Error::InvalidRequest(format!(
+                "price must be a positive amount, got {incl} incl. vat"
+            )));
+        }
+
+        Ok(SitePrice {
+            currency_id: currency_id.to_ascii_uppercase(),
+            vat,
+            cost_per_kwh: incl,
+            cost_per_kwh_exclude_vat: excl,
+        })
+    }
+
+    pub async fn send(&self, client: &Client) -> Result<SitePrice> {
+        let body = self.site_price()?;
+
+        let site_id = self.site_id;
+        let url = format!("api/sites/{site_id}/price");
+
+        client
+            .req::<_, BytesBody>(http::Method::POST, &url, JsonBody(&body))
+            .await?;
+
+        Ok(body)
+    }
+}
+
+/// Prices are rounded to 4 decimals, so that the derived counterpart is free from float noise.
+fn round(price: f64) -> f64 {
+    (price * 10_000.0).round() / 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_counterpart() {
+        let price = UpdateSitePrice::new(SiteId(1), "sek")
+            .vat(25.0)
+            .price_including_vat(2.19)
+            .site_price()
+            .expect("valid");
+
+        assert_eq!(price.currency_id, "SEK");
+        assert_eq!(price.cost_per_kwh, 2.19);
+        assert_eq!(price.cost_per_kwh_exclude_vat, 1.752);
+
+        let price = UpdateSitePrice::new(SiteId(1), "SEK")
+            .vat(25.0)
+            .price_excluding_vat(1.752)
+            .site_price()
+            .expect("valid");
+
+        assert_eq!(price.cost_per_kwh, 2.19);
+    }
+
+    #[test]
+    fn validates() {
+        let req = UpdateSitePrice::new(SiteId(1), "SEK").vat(25.0);
+
+        req.site_price().expect_err("missing price");
+        req.clone()
+            .vat(125.0)
+            .price_including_vat(1.0)
+            .site_price()
+            .expect_err("vat out of range");
+        req.clone()
+            .price_including_vat(-1.0)
+            .site_price()
+            .expect_err("negative price");
+
+        UpdateSitePrice::new(SiteId(1), "kronor")
+            .price_including_vat(1.0)
+            .site_price()
+            .expect_err("invalid currency");
+    }
+
+    #[test]
+    fn serialize() {
+        let price = UpdateSitePrice::new(SiteId(1), "EUR")
+            .price_excluding_vat(0.3)
+            .site_price()
+            .expect("valid");
+
+        assert_eq!(
+            serde_json::to_string(&price).expect("serializing"),
+            r#"{"currencyId":"EUR","vat":0.0,"costPerKWh":0.3,"costPerKwhExcludeVat":0.3}"#
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! Following a charger's ongoing session until its final record is available.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::{
+    ChargerSession, Client, DateTime, Error, OptionalResult, Result,
+    requests::{GetChargerSessions, GetOngoingSession},
+};
+
+/// How long an ended session is looked for in the history before it's given up on
+const GIVE_UP_AFTER: time::Duration = time::Duration::days(1);
+
+/// A session that's no longer ongoing, but whose final record hasn't been found yet
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndedSession {
+    /// As last returned by `GetOngoingSession`
+    pub last_seen: ChargerSession,
+    pub noticed_at: DateTime,
+}
+
+/// Everything needed to pick up where the tracker left off after a restart
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackerState {
+    pub ongoing: Option<ChargerSession>,
+    #[serde(default = "Vec::new")]
+    pub ended: Vec<EndedSession>,
+}
+
+impl TrackerState {
+    /// Returns the default state if `path` doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = match std::fs::read(path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(io_error(path, err)),
+        };
+
+        serde_json::from_slice(&json).map_err(|err| Error::DeserializingJson {
+            err,
+            body: String::from_utf8_lossy(&json).into_owned(),
+        })
+    }
+
+    /// Writes to a temporary file first, so a crash never leaves a half written state behind
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self).expect("serializing tracker state");
+
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(|err| io_error(&tmp, err))?;
+        std::fs::rename(&tmp, path).map_err(|err| io_error(path, err))
+    }
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> Error {
+    Error::Io(format!("{}: {err}", path.display()))
+}
+
+/// Tracks the ongoing session of one charger by `ChargerSession.id`.
+///
+/// Once `GetOngoingSession` stops returning a session (or returns a different one), the
+/// session is looked up by id in `GetChargerSessions` to get the final kWh and cost. The
+/// history can lag behind, so ended sessions are retried on every poll for up to a day.
+pub struct SessionTracker {
+    charger_id: String,
+    state: TrackerState,
+    /// The state as last saved to `path`
+    persisted: TrackerState,
+    path: Option<PathBuf>,
+}
+
+impl SessionTracker {
+    pub fn new(charger_id: impl Into<String>) -> Self {
+        Self {
+            charger_id: charger_id.into(),
+            state: TrackerState::default(),
+            persisted: TrackerState::default(),
+            path: None,
+        }
+    }
+
+    /// Loads the state saved at `path`, if any, and saves it there whenever it changes
+    pub fn persisted(charger_id: impl Into<String>, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let state = TrackerState::load(&path)?;
+
+        Ok(Self {
+            charger_id: charger_id.into(),
+            persisted: state.clone(),
+            state,
+            path: Some(path),
+        })
+    }
+
+    pub fn state(&self) -> &TrackerState {
+        &self.state
+    }
+
+    /// Checks the ongoing session and returns the sessions which were finalized
+    pub async fn poll(&mut self, client: &Client) -> Result<Vec<ChargerSession>> {
+        let ongoing = GetOngoingSession::new(&self.charger_id)
+            .send(client)
+            .await
+            .optional()?;
+        self.observe(ongoing, DateTime::now_utc());
+        // Before looking in the history, which may fail
+        self.persist()?;
+
+        let mut finalized = Vec::new();
+        if let Some(first) = self
+            .state
+            .ended
+            .iter()
+            .map(|e| e.last_seen.car_connected.0)
+            .min()
+        {
+            // Session dates are in UTC, the extra day on each side covers the api
+            // interpreting them in the site's timezone
+            let from = first.date() - time::Duration::days(1);
+            let to = DateTime::now_utc().0.date() + time::Duration::days(2);
+
+            let history = GetChargerSessions::new(&self.charger_id, from, to)
+                .send(client)
+                .await?;
+            finalized = self.finalize(&history, DateTime::now_utc());
+        }
+
+        self.persist()?;
+
+        Ok(finalized)
+    }
+
+    /// Saves the state if it changed since it was last saved
+    fn persist(&mut self) -> Result<()> {
+        if self.state != self.persisted
+            && let Some(path) = &self.path
+        {
+            self.state.save(path)?;
+            self.persisted = self.state.clone();
+        }
+
+        Ok(())
+    }
+
+    /// Polls every `interval`, sending finalized sessions until `sessions` is closed
+    pub async fn run(
+        &mut self,
+        client: &Client,
+        interval: Duration,
+        sessions: mpsc::Sender<ChargerSession>,
+    ) {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = sessions.closed() => return,
+            }
+
+            let finalized = match self.poll(client).await {
+                Ok(finalized) => finalized,
+                Err(err) => {
+                    warn!(
+                        charger_id = self.charger_id,
+                        "tracking session failed: {err}"
+                    );
+                    continue;
+                }
+            };
+
+            for session in finalized {
+                if sessions.send(session).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn observe(&mut self, ongoing: Option<ChargerSession>, now: DateTime) {
+        let ended = match (self.state.ongoing.take(), &ongoing) {
+            (Some(prev), Some(next)) if prev.id == next.id => None,
+            (prev, _) => prev,
+        };
+
+        if let Some(last_seen) = ended {
+            debug!(
+                charger_id = self.charger_id,
+                session_id = last_seen.id,
+                "session ended"
+            );
+            self.state.ended.push(EndedSession {
+                last_seen,
+                noticed_at: now,
+            });
+        }
+
+        self.state.ongoing = ongoing;
+    }
+
+    fn finalize(&mut self, history: &[ChargerSession], now: DateTime) -> Vec<ChargerSession> {
+        let mut finalized = Vec::new();
+
+        self.state.ended.retain(|ended| {
+            if let Some(session) = history.iter().find(|s| s.id == ended.last_seen.id) {
+                finalized.push(session.clone());
+                return false;
+            }
+
+            if now.0 - ended.noticed_at.0 > GIVE_UP_AFTER {
+                warn!(
+                    charger_id = self.charger_id,
+                    session_id = ended.last_seen.id,
+                    "final record of session never showed up, giving up"
+                );
+                return false;
+            }
+
+            true
+        });
+
+        finalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: i64, kwh: f64, ended: bool) -> ChargerSession {
+        let mut json = serde_json::json!({
+            "id": id,
+            "carConnected": "2023-01-20T19:31:46Z",
+            "kiloWattHours": kwh,
+            "pricePerKwhExcludingVat": 1.0,
+            "pricePrKwhIncludingVat": 1.25,
+            "costExcludingVat": kwh,
+            "costIncludingVat": kwh * 1.25,
+        });
+        if ended {
+            json["carDisconnected"] = "2023-01-20T22:00:00Z".into();
+        }
+
+        serde_json::from_value(json).expect("session")
+    }
+
+    fn at(s: &str) -> DateTime {
+        s.parse().expect("datetime")
+    }
+
+    #[test]
+    fn finalizes_by_id() {
+        let mut tracker = SessionTracker::new("EC3VJ7GU");
+        let now = at("2023-01-20T22:05:00Z");
+
+        tracker.observe(Some(session(4, 1.0, false)), now);
+        tracker.observe(Some(session(4, 5.0, false)), now);
+        assert!(tracker.state.ended.is_empty());
+
+        // A new session replacing the old one ends it too
+        tracker.observe(Some(session(5, 0.0, false)), now);
+        tracker.observe(None, now);
+        assert_eq!(tracker.state.ended.len(), 2);
+        assert_eq!(tracker.state.ended[0].last_seen.kilo_watt_hours, 5.0);
+
+        // Only session 4 has made it into the history yet
+        let finalized = tracker.finalize(&[session(3, 2.0, true), session(4, 7.5, true)], now);
+        assert_eq!(finalized, vec![session(4, 7.5, true)]);
+        assert_eq!(tracker.state.ended.len(), 1);
+
+        assert!(tracker.finalize(&[], at("2023-01-21T22:06:00Z")).is_empty());
+        assert!(tracker.state.ended.is_empty());
+    }
+
+    #[test]
+    fn state_survives_restart() {
+        let path = std::env::temp_dir().join(format!("easee-tracker-{}.json", std::process::id()));
+
+        let mut tracker = SessionTracker::persisted("EC3VJ7GU", &path).expect("new");
+        tracker.observe(Some(session(4, 1.0, false)), at("2023-01-20T20:00:00Z"));
+        tracker.observe(None, at("2023-01-20T22:05:00Z"));
+        tracker.state.save(&path).expect("save");
+
+        let restarted = SessionTracker::persisted("EC3VJ7GU", &path).expect("load");
+        std::fs::remove_file(&path).expect("cleanup");
+
+        assert_eq!(restarted.state, tracker.state);
+    }
+
+    #[test]
+    fn saved_after_failed_poll() {
+        let path =
+            std::env::temp_dir().join(format!("easee-tracker-failed-{}.json", std::process::id()));
+        let mut tracker = SessionTracker::persisted("EC3VJ7GU", &path).expect("new");
+
+        tracker.observe(Some(session(4, 1.0, false)), at("2023-01-20T20:00:00Z"));
+        tracker.persist().expect("persist");
+
+        // The session ends, and the history fetch of that poll fails before the end of `poll`
+        tracker.observe(None, at("2023-01-20T22:05:00Z"));
+
+        // The next poll sees no further change, but the earlier one is still unsaved
+        tracker.observe(None, at("2023-01-20T22:06:00Z"));
+        tracker.persist().expect("persist");
+
+        let restarted = SessionTracker::persisted("EC3VJ7GU", &path).expect("load");
+        std::fs::remove_file(&path).expect("cleanup");
+
+        assert_eq!(restarted.state.ended.len(), 1);
+        assert_eq!(restarted.state, tracker.state);
+    }
+}
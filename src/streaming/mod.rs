@@ -0,0 +1,423 @@
+//! Real-time product updates pushed by Easee over its SignalR hub.
+//!
+//! ```no_run
+//! # async fn run(client: easeeapi::Client) {
+//! use futures_util::StreamExt;
+//!
+//! let mut stream = easeeapi::streaming::ProductStream::connect(&client, ["EC3VJ7GU"]);
+//!
+//! while let Some(event) = stream.next().await {
+//!     println!("{event:?}");
+//! }
+//! # }
+//! ```
+
+mod signalr;
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde_json::Value;
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_tungstenite::tungstenite;
+use tracing::{debug, warn};
+
+use crate::{Client, DataType, DateTime, Error, ObservationId, ObservationValue, Result};
+use signalr::Message;
+
+static STREAM_URL: &str = "https://streams.easee.com/hubs/products";
+
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// An observation pushed for a charger or equalizer
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductUpdate {
+    /// Id of the charger or equalizer
+    pub mid: String,
+    pub data_type: DataType,
+    pub id: ObservationId,
+    pub timestamp: DateTime,
+    pub value: String,
+}
+
+impl ProductUpdate {
+    /// The value converted according to its `data_type`
+    pub fn typed_value(&self) -> Result<ObservationValue> {
+        ObservationValue::from_raw(self.data_type, &self.value)
+    }
+}
+
+/// Result of a command sent to a charger, eg `start_charging`
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct StreamCommandResponse {
+    #[serde(rename = "SerialNumber", alias = "serialNumber")]
+    pub serial_number: String,
+    #[serde(rename = "ID", alias = "id")]
+    pub id: i64,
+    #[serde(rename = "Timestamp", alias = "timestamp")]
+    pub timestamp: DateTime,
+    #[serde(rename = "DeliveredAt", alias = "deliveredAt")]
+    pub delivered_at: Option<DateTime>,
+    #[serde(rename = "WasAccepted", alias = "wasAccepted")]
+    pub was_accepted: bool,
+    #[serde(rename = "ResultCode", alias = "resultCode")]
+    pub result_code: i32,
+    #[serde(rename = "Comment", alias = "comment")]
+    pub comment: Option<String>,
+    #[serde(rename = "Ticks", alias = "ticks")]
+    pub ticks: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    ProductUpdate(ProductUpdate),
+    ChargerUpdate(ProductUpdate),
+    CommandResponse(StreamCommandResponse),
+    /// (Re)connected to the hub and subscribed to all devices
+    Connected,
+    /// Lost the connection, a reconnect will be attempted
+    Disconnected,
+}
+
+/// A `Stream` of events for the subscribed chargers and equalizers.
+///
+/// The connection is driven by a background task which reconnects with backoff, and
+/// reconnects before the access token expires so the hub sees the refreshed token. All
+/// subscriptions are renewed after every reconnect. Dropping the stream closes the connection.
+pub struct ProductStream {
+    rx: mpsc::Receiver<Result<StreamEvent>>,
+    subscribe_tx: mpsc::UnboundedSender<String>,
+    task: JoinHandle<()>,
+}
+
+impl ProductStream {
+    /// Must be called from within a tokio runtime
+    pub fn connect(
+        client: &Client,
+        device_ids: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(256);
+        let (subscribe_tx, subscribe_rx) = mpsc::unbounded_channel();
+
+        let conn = Connection {
+            client: client.clone(),
+            devices: device_ids.into_iter().map(Into::into).collect(),
+            subscribe_rx,
+            tx,
+        };
+
+        Self {
+            rx,
+            subscribe_tx,
+            task: tokio::spawn(conn.run()),
+        }
+    }
+
+    /// Subscribes to another charger or equalizer
+    pub fn subscribe(&self, device_id: impl Into<String>) {
+        // Only fails if the task has ended, which the stream reports on its own.
+        let _ = self.subscribe_tx.send(device_id.into());
+    }
+}
+
+impl Stream for ProductStream {
+    type Item = Result<StreamEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for ProductStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+enum Ended {
+    /// The connection should be reestablished
+    Reconnect,
+    /// The stream was dropped
+    Closed,
+}
+
+struct Connection {
+    client: Client,
+    devices: Vec<String>,
+    subscribe_rx: mpsc::UnboundedReceiver<String>,
+    tx: mpsc::Sender<Result<StreamEvent>>,
+}
+
+type WebSocket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Negotiation {
+    connection_id: String,
+    connection_token: Option<String>,
+}
+
+impl Connection {
+    async fn run(mut self) {
+        let mut backoff = MIN_BACKOFF;
+
+        loop {
+            match self.connect_and_stream(&mut backoff).await {
+                Ok(Ended::Closed) => return,
+
+                // Still wait a little, a hub that keeps closing or a session which is already
+                // expiring shouldn't have us reconnecting in a tight loop
+                Ok(Ended::Reconnect) => tokio::time::sleep(MIN_BACKOFF).await,
+
+                Err(err) => {
+                    warn!("product stream: {err}");
+                    if self.tx.send(Err(err)).await.is_err() {
+                        return;
+                    }
+
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+
+            if self.tx.send(Ok(StreamEvent::Disconnected)).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Resets `backoff` once connected, so only failures in a row keep increasing it
+    async fn connect_and_stream(&mut self, backoff: &mut Duration) -> Result<Ended> {
+        let session = self.client.get_session().await?;
+        let token = session.raw;
+
+        let negotiation = self
+            .client
+            .http_client()
+            .post(format!("{STREAM_URL}/negotiate?negotiateVersion=1"))
+            .header("authorization", format!("Bearer {token}"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Negotiation>()
+            .await?;
+
+        let connection_token = negotiation
+            .connection_token
+            .unwrap_or(negotiation.connection_id);
+
+        let url = hub_url(&connection_token, &token)?;
+
+        let (mut ws, _) = tokio_tungstenite::connect_async(url.as_str())
+            .await
+            .map_err(streaming)?;
+
+        handshake(&mut ws).await?;
+        *backoff = MIN_BACKOFF;
+
+        for device_id in &self.devices {
+            send(&mut ws, subscribe(device_id)).await?;
+        }
+
+        debug!(devices = self.devices.len(), "product stream connected");
+        if self.tx.send(Ok(StreamEvent::Connected)).await.is_err() {
+            return Ok(Ended::Closed);
+        }
+
+        // get_token refreshes the session once it's within a minute of expiring, so reconnect
+        // just after that to have the hub authorize the new token.
+        let until_expiry =
+            session.expires_at.0 - DateTime::now_utc().0 - time::Duration::seconds(55);
+        let refresh = tokio::time::sleep(until_expiry.try_into().unwrap_or(Duration::ZERO));
+        tokio::pin!(refresh);
+
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = &mut refresh => {
+                    debug!("access token about to expire, reconnecting product stream");
+                    let _ = ws.close(None).await;
+                    return Ok(Ended::Reconnect);
+                }
+
+                _ = ping.tick() => send(&mut ws, signalr::ping()).await?,
+
+                device_id = self.subscribe_rx.recv() => {
+                    let Some(device_id) = device_id else {
+                        return Ok(Ended::Closed);
+                    };
+
+                    send(&mut ws, subscribe(&device_id)).await?;
+                    self.devices.push(device_id);
+                }
+
+                frame = ws.next() => {
+                    let text = match frame {
+                        Some(Ok(tungstenite::Message::Text(text))) => text,
+                        Some(Ok(tungstenite::Message::Close(_))) | None => return Ok(Ended::Reconnect),
+                        Some(Ok(_)) => continue,
+                        Some(Err(err)) => return Err(streaming(err)),
+                    };
+
+                    for msg in signalr::decode(&text).map_err(streaming)? {
+                        match msg {
+                            Message::Invocation { target, arguments } => {
+                                for event in to_events(&target, arguments) {
+                                    if self.tx.send(event).await.is_err() {
+                                        return Ok(Ended::Closed);
+                                    }
+                                }
+                            }
+
+                            Message::Close { error: Some(err) } => {
+                                return Err(Error::Streaming(format!("closed by hub: {err}")));
+                            }
+                            Message::Close { error: None } => return Ok(Ended::Reconnect),
+
+                            Message::Completion { error: Some(err) } => {
+                                warn!("product stream invocation failed: {err}");
+                            }
+
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn handshake(ws: &mut WebSocket) -> Result<()> {
+    send(ws, signalr::HANDSHAKE.into()).await?;
+
+    while let Some(frame) = ws.next().await {
+        let text = match frame.map_err(streaming)? {
+            tungstenite::Message::Text(text) => text,
+            tungstenite::Message::Close(_) => break,
+            _ => continue,
+        };
+
+        for msg in signalr::decode(&text).map_err(streaming)? {
+            if let Message::Handshake { error } = msg {
+                return match error {
+                    None => Ok(()),
+                    Some(err) => Err(Error::Streaming(format!("handshake refused: {err}"))),
+                };
+            }
+        }
+    }
+
+    Err(Error::Streaming(
+        "connection closed during handshake".into(),
+    ))
+}
+
+async fn send(ws: &mut WebSocket, msg: String) -> Result<()> {
+    ws.send(tungstenite::Message::Text(msg.into()))
+        .await
+        .map_err(streaming)
+}
+
+fn subscribe(device_id: &str) -> String {
+    signalr::invocation(
+        "SubscribeWithCurrentState",
+        &[device_id.into(), true.into()],
+    )
+}
+
+fn to_events(target: &str, arguments: Vec<Value>) -> Vec<Result<StreamEvent>> {
+    arguments
+        .into_iter()
+        .filter_map(|arg| {
+            let event = match target {
+                "ProductUpdate" => {
+                    serde_json::from_value(arg.clone()).map(StreamEvent::ProductUpdate)
+                }
+                "ChargerUpdate" => {
+                    serde_json::from_value(arg.clone()).map(StreamEvent::ChargerUpdate)
+                }
+                "CommandResponse" => {
+                    serde_json::from_value(arg.clone()).map(StreamEvent::CommandResponse)
+                }
+                _ => return None,
+            };
+
+            Some(event.map_err(|err| Error::DeserializingJson {
+                err,
+                body: arg.to_string(),
+            }))
+        })
+        .collect()
+}
+
+/// The websocket url of the hub, for a connection token from negotiating
+fn hub_url(connection_token: &str, access_token: &str) -> Result<url::Url> {
+    let mut url =
+        url::Url::parse(&STREAM_URL.replacen("https://", "wss://", 1)).map_err(streaming)?;
+    url.query_pairs_mut()
+        .append_pair("id", connection_token)
+        .append_pair("access_token", access_token);
+
+    Ok(url)
+}
+
+fn streaming(err: impl std::fmt::Display) -> Error {
+    Error::Streaming(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_update_events() {
+        let args = serde_json::json!([
+            {"mid":"EC3VJ7GU","dataType":3,"id":120,"timestamp":"2023-08-20T12:00:00.123Z","value":"3.68"}
+        ]);
+
+        let events = to_events("ProductUpdate", serde_json::from_value(args).expect("args"));
+        let Ok(StreamEvent::ProductUpdate(update)) = &events[0] else {
+            panic!("expected product update, got {events:?}");
+        };
+
+        assert_eq!(update.id, ObservationId::TotalPower);
+        assert_eq!(
+            update.typed_value().unwrap(),
+            ObservationValue::Double(3.68)
+        );
+
+        assert!(to_events("SomethingElse", vec![Value::Null]).is_empty());
+    }
+
+    #[test]
+    fn command_response_event() {
+        let args = serde_json::json!([
+            {"SerialNumber":"EC3VJ7GU","ID":48,"Timestamp":"2023-08-20T12:00:00Z","DeliveredAt":"2023-08-20T12:00:01Z","WasAccepted":true,"ResultCode":0,"Comment":null,"Ticks":638281440000000000i64}
+        ]);
+
+        let events = to_events(
+            "CommandResponse",
+            serde_json::from_value(args).expect("args"),
+        );
+        assert!(matches!(&events[0], Ok(StreamEvent::CommandResponse(res)) if res.was_accepted));
+    }
+
+    #[test]
+    fn hub_url_is_encoded() {
+        let url = hub_url("a+b/c==", "eyJ.x+y/z=").expect("url");
+
+        assert_eq!(
+            url.as_str(),
+            "wss://streams.easee.com/hubs/products?id=a%2Bb%2Fc%3D%3D&access_token=eyJ.x%2By%2Fz%3D"
+        );
+    }
+}
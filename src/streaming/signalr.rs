@@ -0,0 +1,121 @@
+//! The minimal subset of the SignalR JSON hub protocol needed to talk to Easee.
+//!
+//! Every message is a JSON object terminated by the record separator `0x1e`,
+//! and a single websocket frame may hold several messages.
+
+use serde_json::Value;
+
+pub(crate) const RECORD_SEPARATOR: char = '\u{1e}';
+
+pub(crate) const HANDSHAKE: &str = "{\"protocol\":\"json\",\"version\":1}\u{1e}";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Message {
+    /// Reply to the handshake, `error` set if it was refused
+    Handshake {
+        error: Option<String>,
+    },
+    Invocation {
+        target: String,
+        arguments: Vec<Value>,
+    },
+    Completion {
+        error: Option<String>,
+    },
+    Ping,
+    Close {
+        error: Option<String>,
+    },
+    Other,
+}
+
+#[derive(serde::Deserialize)]
+struct RawMessage {
+    #[serde(rename = "type")]
+    kind: Option<u8>,
+    target: Option<String>,
+    #[serde(default)]
+    arguments: Vec<Value>,
+    error: Option<String>,
+}
+
+pub(crate) fn decode(frame: &str) -> Result<Vec<Message>, serde_json::Error> {
+    frame
+        .split(RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let raw = serde_json::from_str::<RawMessage>(record)?;
+
+            Ok(match raw.kind {
+                None => Message::Handshake { error: raw.error },
+                Some(1) => Message::Invocation {
+                    target: raw.target.unwrap_or_default(),
+                    arguments: raw.arguments,
+                },
+                Some(3) => Message::Completion { error: raw.error },
+                Some(6) => Message::Ping,
+                Some(7) => Message::Close { error: raw.error },
+                Some(_) => Message::Other,
+            })
+        })
+        .collect()
+}
+
+/// Encodes an invocation we don't expect a reply to
+pub(crate) fn invocation(target: &str, arguments: &[Value]) -> String {
+    let msg = serde_json::json!({
+        "type": 1,
+        "target": target,
+        "arguments": arguments,
+    });
+
+    format!("{msg}{RECORD_SEPARATOR}")
+}
+
+pub(crate) fn ping() -> String {
+    format!("{{\"type\":6}}{RECORD_SEPARATOR}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_frames() {
+        assert_eq!(
+            decode("{}\u{1e}").expect("decoding"),
+            vec![Message::Handshake { error: None }]
+        );
+
+        let frame = concat!(
+            r#"{"type":6}"#,
+            "\u{1e}",
+            r#"{"type":1,"target":"ProductUpdate","arguments":[{"mid":"EC3VJ7GU","dataType":3,"id":120,"timestamp":"2023-08-20T12:00:00Z","value":"3.68"}]}"#,
+            "\u{1e}",
+        );
+
+        let msgs = decode(frame).expect("decoding");
+        assert_eq!(msgs[0], Message::Ping);
+        assert!(
+            matches!(&msgs[1], Message::Invocation { target, arguments } if target == "ProductUpdate" && arguments.len() == 1)
+        );
+
+        assert_eq!(
+            decode(r#"{"type":7,"error":"Unauthorized"}"#).expect("decoding"),
+            vec![Message::Close {
+                error: Some("Unauthorized".into())
+            }]
+        );
+    }
+
+    #[test]
+    fn encode_invocation() {
+        assert_eq!(
+            invocation(
+                "SubscribeWithCurrentState",
+                &["EC3VJ7GU".into(), true.into()]
+            ),
+            "{\"arguments\":[\"EC3VJ7GU\",true],\"target\":\"SubscribeWithCurrentState\",\"type\":1}\u{1e}"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! A minimal http server for testing what requests send, answering each connection with the
+//! next canned reply.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread::JoinHandle,
+};
+
+use base64::{Engine, engine::general_purpose};
+
+use crate::Client;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recorded {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+pub struct TestServer {
+    url: String,
+    handle: JoinHandle<Vec<Recorded>>,
+}
+
+impl TestServer {
+    /// Serves `(status, body)` replies in order, one per connection
+    pub fn start(replies: Vec<(u16, &'static str)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binding test server");
+        let url = format!("http://{}", listener.local_addr().expect("local addr"));
+
+        let handle = std::thread::spawn(move || {
+            replies
+                .into_iter()
+                .map(|(status, body)| {
+                    let (stream, _) = listener.accept().expect("accepting");
+                    let mut reader = BufReader::new(&stream);
+
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).expect("request line");
+                    let mut parts = request_line.split_whitespace();
+                    let method = parts.next().unwrap_or_default().to_string();
+                    let path = parts.next().unwrap_or_default().to_string();
+
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).expect("header");
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = line.split_once(':')
+                            && name.eq_ignore_ascii_case("content-length")
+                        {
+                            content_length = value.trim().parse().expect("content-length");
+                        }
+                    }
+
+                    let mut request_body = vec![0; content_length];
+                    reader.read_exact(&mut request_body).expect("body");
+
+                    write!(
+                        &stream,
+                        "HTTP/1.1 {status} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .expect("replying");
+
+                    Recorded {
+                        method,
+                        path,
+                        body: String::from_utf8(request_body).expect("utf8 body"),
+                    }
+                })
+                .collect()
+        });
+
+        Self { url, handle }
+    }
+
+    /// A client with a session valid for decades, sending to this server
+    pub fn client(&self) -> Client {
+        let claims = general_purpose::STANDARD_NO_PAD
+            .encode(r#"{"exp":4102444800,"iat":1692916359,"role":["User"]}"#);
+
+        Client::new(format!("e30.{claims}.sig"), "refresh")
+            .expect("test token")
+            .with_base_url(&self.url)
+    }
+
+    /// The requests received, once every reply has been sent
+    pub fn requests(self) -> Vec<Recorded> {
+        self.handle.join().expect("test server")
+    }
+}
@@ -0,0 +1,340 @@
+//! Charger events by polling, for when the streaming hub can't be reached.
+
+use std::time::Duration;
+
+use tokio::{sync::mpsc, time::Instant};
+use tracing::{debug, warn};
+
+use crate::{
+    ChargerOpMode, ChargerSession, ChargerState, Client, DateTime, Error, OptionalResult, Result,
+    requests::{GetChargerState, GetOngoingSession},
+};
+
+/// Something that changed on a charger between two polls
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChargerEvent {
+    CarConnected,
+    ChargingStarted,
+    ChargingPaused,
+    /// The car stopped charging on its own, usually because it's full
+    ChargingCompleted {
+        session: Option<ChargerSession>,
+    },
+    /// The car was unplugged, with the session as last seen while it was connected
+    CarDisconnected {
+        session: Option<ChargerSession>,
+    },
+    WentOffline,
+    CameOnline,
+    Error {
+        code: Option<i32>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchEvent {
+    pub charger_id: String,
+    /// When the change was noticed, which can be up to one poll interval after it happened
+    pub observed_at: DateTime,
+    pub event: ChargerEvent,
+}
+
+/// What the watcher remembers about a charger between polls
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    op_mode: ChargerOpMode,
+    online: bool,
+    error_code: Option<i32>,
+    session: Option<ChargerSession>,
+}
+
+impl Snapshot {
+    fn new(state: &ChargerState, session: Option<ChargerSession>) -> Self {
+        Self {
+            op_mode: state.charger_op_mode,
+            online: state
+                .is_online
+                .unwrap_or(state.charger_op_mode != ChargerOpMode::Offline),
+            error_code: state.error_code.filter(|code| *code != 0),
+            session,
+        }
+    }
+
+    /// `None` when the op mode doesn't tell, eg while offline or in error
+    fn car_connected(&self) -> Option<bool> {
+        match self.op_mode {
+            ChargerOpMode::Disconnected => Some(false),
+            ChargerOpMode::AwaitingStart
+            | ChargerOpMode::Charging
+            | ChargerOpMode::Completed
+            | ChargerOpMode::ReadyToCharge
+            | ChargerOpMode::AwaitingAuthentication
+            | ChargerOpMode::Deauthenticating => Some(true),
+            ChargerOpMode::Offline | ChargerOpMode::Error | ChargerOpMode::Unknown(_) => None,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.online && self.car_connected() == Some(true)
+    }
+}
+
+fn diff(prev: &Snapshot, next: &Snapshot) -> Vec<ChargerEvent> {
+    let mut events = Vec::new();
+
+    if prev.online && !next.online {
+        events.push(ChargerEvent::WentOffline);
+    } else if !prev.online && next.online {
+        events.push(ChargerEvent::CameOnline);
+    }
+
+    match (prev.car_connected(), next.car_connected()) {
+        (Some(false), Some(true)) => events.push(ChargerEvent::CarConnected),
+        (Some(true), Some(false)) => {
+            if prev.op_mode == ChargerOpMode::Charging {
+                events.push(ChargerEvent::ChargingCompleted {
+                    session: prev.session.clone(),
+                });
+            }
+
+            events.push(ChargerEvent::CarDisconnected {
+                session: prev.session.clone(),
+            });
+            return events;
+        }
+        _ => {}
+    }
+
+    use ChargerOpMode::*;
+    match (prev.op_mode, next.op_mode) {
+        (Charging, Charging) => {}
+        (_, Charging) => events.push(ChargerEvent::ChargingStarted),
+        (Charging, AwaitingStart | ReadyToCharge) => events.push(ChargerEvent::ChargingPaused),
+        (Charging, Completed) => events.push(ChargerEvent::ChargingCompleted {
+            session: next.session.clone().or_else(|| prev.session.clone()),
+        }),
+        _ => {}
+    }
+
+    let entered_error = next.op_mode == Error && prev.op_mode != Error;
+    if entered_error || (next.error_code.is_some() && next.error_code != prev.error_code) {
+        events.push(ChargerEvent::Error {
+            code: next.error_code,
+        });
+    }
+
+    events
+}
+
+/// Polls charger state, and the ongoing session while a car is connected, and sends the
+/// differences between polls as [`WatchEvent`]s.
+///
+/// Chargers with a car connected are polled every `active_interval`, others every
+/// `idle_interval`. Failed polls back off up to `idle_interval`, and straight to it when rate
+/// limited. All requests go through the client, so they share its rate limit (see
+/// [`Client::with_rate_limit`]) with everything else using it.
+pub struct ChargerWatcher {
+    charger_ids: Vec<String>,
+    active_interval: Duration,
+    idle_interval: Duration,
+}
+
+struct Watched {
+    charger_id: String,
+    next_poll: Instant,
+    snapshot: Option<Snapshot>,
+    failures: u32,
+}
+
+impl ChargerWatcher {
+    pub fn new(charger_ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            charger_ids: charger_ids.into_iter().map(Into::into).collect(),
+            active_interval: Duration::from_secs(30),
+            idle_interval: Duration::from_secs(5 * 60),
+        }
+    }
+
+    /// How often chargers with a car connected are polled
+    pub fn active_interval(mut self, active_interval: Duration) -> Self {
+        self.active_interval = active_interval;
+        self
+    }
+
+    /// How often chargers without a car, or offline, are polled
+    pub fn idle_interval(mut self, idle_interval: Duration) -> Self {
+        self.idle_interval = idle_interval;
+        self
+    }
+
+    /// Polls until `events` is closed. The first poll of each charger only sets the baseline.
+    pub async fn run(&self, client: &Client, events: mpsc::Sender<WatchEvent>) {
+        let now = Instant::now();
+        let mut watched = self
+            .charger_ids
+            .iter()
+            .map(|charger_id| Watched {
+                charger_id: charger_id.clone(),
+                next_poll: now,
+                snapshot: None,
+                failures: 0,
+            })
+            .collect::<Vec<_>>();
+
+        loop {
+            let Some(charger) = watched.iter_mut().min_by_key(|w| w.next_poll) else {
+                return;
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep_until(charger.next_poll) => {}
+                _ = events.closed() => return,
+            }
+
+            let next = match poll(client, &charger.charger_id).await {
+                Ok(next) => next,
+                Err(err) => {
+                    charger.failures += 1;
+
+                    // Being rate limited affects every charger, so don't bother retrying soon
+                    let rate_limited = matches!(
+                        err,
+                        Error::Api {
+                            http_status: 429,
+                            ..
+                        }
+                    );
+                    let backoff = if rate_limited {
+                        self.idle_interval
+                    } else {
+                        self.active_interval
+                            .saturating_mul(2u32.saturating_pow(charger.failures))
+                            .min(self.idle_interval)
+                    };
+
+                    warn!(
+                        charger_id = charger.charger_id,
+                        rate_limited, "polling charger failed: {err}"
+                    );
+
+                    charger.next_poll = Instant::now() + backoff;
+                    continue;
+                }
+            };
+
+            charger.failures = 0;
+            charger.next_poll = Instant::now()
+                + if next.is_active() {
+                    self.active_interval
+                } else {
+                    self.idle_interval
+                };
+
+            if let Some(prev) = &charger.snapshot {
+                let observed_at = DateTime::now_utc();
+
+                for event in diff(prev, &next) {
+                    debug!(charger_id = charger.charger_id, ?event, "charger event");
+
+                    let event = WatchEvent {
+                        charger_id: charger.charger_id.clone(),
+                        observed_at,
+                        event,
+                    };
+
+                    if events.send(event).await.is_err() {
+                        return;
+                    }
+                }
+            }
+
+            charger.snapshot = Some(next);
+        }
+    }
+}
+
+async fn poll(client: &Client, charger_id: &str) -> Result<Snapshot> {
+    let state = GetChargerState::new(charger_id).send(client).await?;
+    let mut snapshot = Snapshot::new(&state, None);
+
+    if snapshot.car_connected() == Some(true) {
+        snapshot.session = GetOngoingSession::new(charger_id)
+            .send(client)
+            .await
+            .optional()?;
+    }
+
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(op_mode: ChargerOpMode) -> Snapshot {
+        Snapshot {
+            op_mode,
+            online: op_mode != ChargerOpMode::Offline,
+            error_code: None,
+            session: None,
+        }
+    }
+
+    #[test]
+    fn charging_lifecycle() {
+        use ChargerOpMode::*;
+
+        let steps = [
+            (
+                Disconnected,
+                AwaitingStart,
+                vec![ChargerEvent::CarConnected],
+            ),
+            (AwaitingStart, Charging, vec![ChargerEvent::ChargingStarted]),
+            (Charging, ReadyToCharge, vec![ChargerEvent::ChargingPaused]),
+            (ReadyToCharge, Charging, vec![ChargerEvent::ChargingStarted]),
+            (
+                Charging,
+                Completed,
+                vec![ChargerEvent::ChargingCompleted { session: None }],
+            ),
+            (
+                Completed,
+                Disconnected,
+                vec![ChargerEvent::CarDisconnected { session: None }],
+            ),
+            (Charging, Charging, vec![]),
+        ];
+
+        for (prev, next, expected) in steps {
+            assert_eq!(
+                diff(&snapshot(prev), &snapshot(next)),
+                expected,
+                "{prev:?} -> {next:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn offline_and_errors() {
+        use ChargerOpMode::*;
+
+        assert_eq!(
+            diff(&snapshot(Charging), &snapshot(Offline)),
+            vec![ChargerEvent::WentOffline]
+        );
+        // Whether a car was connected is unknown while offline, so no `CarConnected`
+        assert_eq!(
+            diff(&snapshot(Offline), &snapshot(Charging)),
+            vec![ChargerEvent::CameOnline, ChargerEvent::ChargingStarted]
+        );
+
+        let mut failing = snapshot(Error);
+        failing.error_code = Some(11);
+        assert_eq!(
+            diff(&snapshot(Disconnected), &failing),
+            vec![ChargerEvent::Error { code: Some(11) }]
+        );
+        assert_eq!(diff(&failing, &failing), vec![]);
+    }
+}